@@ -0,0 +1,68 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use remem::Pool;
+use std::thread;
+
+const CAPACITIES: [usize; 3] = [64, 4 * 1024, 64 * 1024];
+const THREADS: [usize; 3] = [2, 4, 10];
+const ITER: usize = 1000;
+
+fn run(threads: usize, iter: usize, capacity: usize) {
+    let p = Pool::new(move || vec![0u8; capacity]);
+    let mut handles = Vec::new();
+
+    for _ in 0..threads {
+        let p = p.clone();
+        handles.push(thread::spawn(move || {
+            for _ in 0..iter {
+                let mut v = black_box(p.get());
+                v[0] = 1;
+                v[capacity / 4] = 1;
+                v[capacity / 2] = 1;
+            }
+        }));
+    }
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+fn create(c: &mut Criterion) {
+    let mut group = c.benchmark_group("create");
+    for capacity in CAPACITIES {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(capacity),
+            &capacity,
+            |b, &capacity| b.iter(|| Pool::new(move || vec![0u8; capacity])),
+        );
+    }
+    group.finish();
+}
+
+fn no_contention(c: &mut Criterion) {
+    let mut group = c.benchmark_group("no_contention");
+    for capacity in CAPACITIES {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(capacity),
+            &capacity,
+            |b, &capacity| b.iter(|| run(1, ITER, capacity)),
+        );
+    }
+    group.finish();
+}
+
+fn contention(c: &mut Criterion) {
+    let mut group = c.benchmark_group("contention");
+    for threads in THREADS {
+        for capacity in CAPACITIES {
+            let id = BenchmarkId::new(format!("{}_threads", threads), capacity);
+            group.bench_with_input(id, &(threads, capacity), |b, &(threads, capacity)| {
+                b.iter(|| run(threads, ITER, capacity))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, create, no_contention, contention);
+criterion_main!(benches);
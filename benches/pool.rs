@@ -17,16 +17,28 @@ mod remem {
 
     #[bench]
     fn contention(b: &mut Bencher) {
-        b.iter(|| run(10, 1000));
+        b.iter(|| run(10, 1000, 0));
+    }
+
+    #[bench]
+    fn contention_spin_4(b: &mut Bencher) {
+        b.iter(|| run(10, 1000, 4));
+    }
+
+    #[bench]
+    fn contention_spin_16(b: &mut Bencher) {
+        b.iter(|| run(10, 1000, 16));
     }
 
     #[bench]
     fn no_contention(b: &mut Bencher) {
-        b.iter(|| run(1, 1000));
+        b.iter(|| run(1, 1000, 0));
     }
 
-    fn run(thread: usize, iter: usize) {
-        let p = Pool::new(|| vec![0u8; CAPACITY]);
+    fn run(thread: usize, iter: usize, spin_tries: usize) {
+        let p = Pool::builder(|| vec![0u8; CAPACITY])
+            .spin_tries(spin_tries)
+            .build();
         let mut threads = Vec::new();
 
         for _ in 0..thread {
@@ -56,7 +68,7 @@ mod byte_pool {
 
     #[bench]
     fn create(b: &mut Bencher) {
-        b.iter(|| Arc::new(BytePool::new()));
+        b.iter(|| Arc::new(BytePool::<Vec<u8>>::new()));
     }
 
     #[bench]
@@ -70,7 +82,7 @@ mod byte_pool {
     }
 
     fn run(thread: usize, iter: usize) {
-        let p = Arc::new(BytePool::new());
+        let p = Arc::new(BytePool::<Vec<u8>>::new());
         let mut threads = Vec::new();
 
         for _ in 0..thread {
@@ -91,6 +103,98 @@ mod byte_pool {
     }
 }
 
+mod sharded_slab {
+    use super::CAPACITY;
+    use sharded_slab::Pool;
+    use std::sync::Arc;
+    use std::thread;
+    use test::{black_box, Bencher};
+
+    #[bench]
+    fn create(b: &mut Bencher) {
+        b.iter(|| Arc::new(Pool::<Vec<u8>>::new()));
+    }
+
+    #[bench]
+    fn contention(b: &mut Bencher) {
+        b.iter(|| run(10, 1000));
+    }
+
+    #[bench]
+    fn no_contention(b: &mut Bencher) {
+        b.iter(|| run(1, 1000));
+    }
+
+    fn run(thread: usize, iter: usize) {
+        let p = Arc::new(Pool::<Vec<u8>>::new());
+        let mut threads = Vec::new();
+
+        for _ in 0..thread {
+            let p = p.clone();
+            threads.push(thread::spawn(move || {
+                for _ in 0..iter {
+                    let mut v = black_box(p.create().unwrap());
+                    // Slots are cleared when they're released, which keeps the
+                    // allocation but not the length.
+                    v.resize(CAPACITY, 0);
+                    v[0] = 1;
+                    v[CAPACITY / 4] = 1;
+                    v[CAPACITY / 2] = 1;
+                }
+            }));
+        }
+
+        for t in threads {
+            t.join().unwrap();
+        }
+    }
+}
+
+mod lockfree {
+    use super::CAPACITY;
+    use lockfree::stack::Stack;
+    use std::sync::Arc;
+    use std::thread;
+    use test::{black_box, Bencher};
+
+    #[bench]
+    fn create(b: &mut Bencher) {
+        b.iter(|| Arc::new(Stack::<Vec<u8>>::new()));
+    }
+
+    #[bench]
+    fn contention(b: &mut Bencher) {
+        b.iter(|| run(10, 1000));
+    }
+
+    #[bench]
+    fn no_contention(b: &mut Bencher) {
+        b.iter(|| run(1, 1000));
+    }
+
+    fn run(thread: usize, iter: usize) {
+        let p = Arc::new(Stack::new());
+        let mut threads = Vec::new();
+
+        for _ in 0..thread {
+            let p = p.clone();
+            threads.push(thread::spawn(move || {
+                for _ in 0..iter {
+                    let mut v = black_box(p.pop().unwrap_or_else(|| vec![0u8; CAPACITY]));
+                    v[0] = 1;
+                    v[CAPACITY / 4] = 1;
+                    v[CAPACITY / 2] = 1;
+                    p.push(v);
+                }
+            }));
+        }
+
+        for t in threads {
+            t.join().unwrap();
+        }
+    }
+}
+
 mod vec {
     use super::CAPACITY;
     use std::thread;
@@ -130,3 +234,102 @@ mod vec {
         }
     }
 }
+
+mod copy {
+    use remem::Pool;
+    use test::{black_box, Bencher};
+
+    #[bench]
+    fn pooled(b: &mut Bencher) {
+        let p = Pool::new(|| 0u64);
+        b.iter(|| {
+            let mut v = black_box(p.get());
+            *v += 1;
+        });
+    }
+
+    #[bench]
+    fn fast_path(b: &mut Bencher) {
+        let p = Pool::new(|| 0u64);
+        b.iter(|| {
+            let mut v = black_box(p.get_copy());
+            *v += 1;
+        });
+    }
+
+    #[bench]
+    fn direct(b: &mut Bencher) {
+        b.iter(|| {
+            let mut v = black_box(0u64);
+            v += 1;
+            black_box(v);
+        });
+    }
+}
+
+mod deref {
+    use super::CAPACITY;
+    use remem::Pool;
+    use test::{black_box, Bencher};
+
+    #[bench]
+    fn guard(b: &mut Bencher) {
+        let p = Pool::new(|| vec![0u8; CAPACITY]);
+        let mut v = p.get();
+        b.iter(|| {
+            for i in 0..CAPACITY {
+                v[i] = v[i].wrapping_add(black_box(1));
+            }
+        });
+    }
+
+    #[bench]
+    fn direct(b: &mut Bencher) {
+        let mut v = vec![0u8; CAPACITY];
+        b.iter(|| {
+            for i in 0..CAPACITY {
+                v[i] = v[i].wrapping_add(black_box(1));
+            }
+        });
+    }
+}
+
+#[cfg(feature = "numa")]
+mod numa {
+    use super::CAPACITY;
+    use remem::NumaPool;
+    use std::sync::Arc;
+    use std::thread;
+    use test::{black_box, Bencher};
+
+    #[bench]
+    fn contention(b: &mut Bencher) {
+        b.iter(|| run(10, 1000));
+    }
+
+    #[bench]
+    fn no_contention(b: &mut Bencher) {
+        b.iter(|| run(1, 1000));
+    }
+
+    fn run(thread: usize, iter: usize) {
+        let p = Arc::new(NumaPool::new(|| vec![0u8; CAPACITY]));
+        let mut threads = Vec::new();
+
+        for _ in 0..thread {
+            let p = p.clone();
+            threads.push(thread::spawn(move || {
+                for _ in 0..iter {
+                    let mut v = black_box(p.get());
+                    v[0] = 1;
+                    v[CAPACITY / 4] = 1;
+                    v[CAPACITY / 2] = 1;
+                }
+            }));
+        }
+
+        for t in threads {
+            t.join().unwrap();
+        }
+    }
+}
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../tests/support/concurrent.rs"]
+mod concurrent;
+
+fuzz_target!(|data: &[u8]| concurrent::run(data));
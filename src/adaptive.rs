@@ -0,0 +1,80 @@
+use crate::atomic::{AtomicUsize, Ordering};
+
+/// Limit how many items a pool keeps based on how many are actually used.
+///
+/// The pool tracks the peak number of items checked out at once over a sliding
+/// window of `get` and drop operations, and stops storing returned items once
+/// it holds more than `multiplier` times that peak. After a burst of activity
+/// the pool sheds the excess items as the peak drops.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptivePolicy {
+    multiplier: f64,
+    window: usize,
+}
+
+impl AdaptivePolicy {
+    /// Create a new policy which keeps `multiplier` times the peak number of
+    /// items in use over the last `window` to `2 * window` operations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is 0 or `multiplier` is negative.
+    pub fn new(multiplier: f64, window: usize) -> AdaptivePolicy {
+        assert!(window > 0, "window must be greater than 0");
+        assert!(multiplier >= 0.0, "multiplier must not be negative");
+        AdaptivePolicy { multiplier, window }
+    }
+}
+
+impl Default for AdaptivePolicy {
+    /// Keep 1.2 times the peak over the last 1000 operations.
+    fn default() -> Self {
+        AdaptivePolicy::new(1.2, 1000)
+    }
+}
+
+/// The state of an `AdaptivePolicy` for a single pool.
+///
+/// The window is approximated with two buckets: the peak so far in the current
+/// window, and the peak of the previous one.
+#[derive(Debug)]
+pub(crate) struct Adaptive {
+    policy: AdaptivePolicy,
+    operations: AtomicUsize,
+    current_peak: AtomicUsize,
+    previous_peak: AtomicUsize,
+}
+
+impl Adaptive {
+    pub(crate) fn new(policy: AdaptivePolicy) -> Self {
+        Adaptive {
+            policy,
+            operations: AtomicUsize::new(0),
+            current_peak: AtomicUsize::new(0),
+            previous_peak: AtomicUsize::new(0),
+        }
+    }
+
+    /// Record a `get` or drop, after which `in_use` items are checked out.
+    pub(crate) fn record(&self, in_use: usize) {
+        self.current_peak.fetch_max(in_use, Ordering::Relaxed);
+        let operations = self.operations.fetch_add(1, Ordering::Relaxed) + 1;
+        if operations % self.policy.window == 0 {
+            let peak = self.current_peak.swap(in_use, Ordering::Relaxed);
+            self.previous_peak.store(peak, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the number of items the pool should keep, which is unlimited
+    /// until the first `get` or drop has been recorded.
+    pub(crate) fn limit(&self) -> usize {
+        if self.operations.load(Ordering::Relaxed) == 0 {
+            return usize::MAX;
+        }
+        let peak = self
+            .current_peak
+            .load(Ordering::Relaxed)
+            .max(self.previous_peak.load(Ordering::Relaxed));
+        (peak as f64 * self.policy.multiplier).ceil() as usize
+    }
+}
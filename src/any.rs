@@ -0,0 +1,62 @@
+use crate::ItemGuard;
+use std::any::Any;
+use std::fmt::{self, Debug};
+
+/// A guard with its item type erased, which still returns the item to its
+/// pool when dropped.
+///
+/// This lets guards from pools of different types be stored together. Use
+/// `downcast_ref` and `downcast_mut` to get at the item. Created by
+/// `ItemGuard::into_any`.
+pub struct PooledAny<'a> {
+    guard: Box<dyn Erased + Send + 'a>,
+}
+
+impl Debug for PooledAny<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PooledAny").finish_non_exhaustive()
+    }
+}
+
+/// Gives access to a guard's item without knowing its type.
+trait Erased {
+    fn item(&self) -> &dyn Any;
+    fn item_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> Erased for ItemGuard<'_, T> {
+    fn item(&self) -> &dyn Any {
+        &**self
+    }
+
+    fn item_mut(&mut self) -> &mut dyn Any {
+        &mut **self
+    }
+}
+
+impl<'a> PooledAny<'a> {
+    /// Returns a reference to the item if it's of type `T`.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.guard.item().downcast_ref()
+    }
+
+    /// Returns a mutable reference to the item if it's of type `T`.
+    pub fn downcast_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.guard.item_mut().downcast_mut()
+    }
+
+    /// Returns `true` if the item is of type `T`.
+    pub fn is<T: Any>(&self) -> bool {
+        self.guard.item().is::<T>()
+    }
+}
+
+impl<'a, T: Any + Send> ItemGuard<'a, T> {
+    /// Erase the item's type, so guards from pools of different types can be
+    /// stored together.
+    pub fn into_any(self) -> PooledAny<'a> {
+        PooledAny {
+            guard: Box::new(self),
+        }
+    }
+}
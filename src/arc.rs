@@ -0,0 +1,66 @@
+use crate::{origin::Origin, Pool};
+use std::fmt::{self, Debug};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A pooled item shared through an `Arc`, created by `Pool::get_arc`.
+///
+/// The item is returned to the pool once the last `Arc` pointing to it is
+/// dropped.
+pub struct PooledInner<T> {
+    item: Option<T>,
+    pool: Pool<T>,
+    origin: Origin,
+}
+
+impl<T: Debug> Debug for PooledInner<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PooledInner")
+            .field("item", &self.item)
+            .finish()
+    }
+}
+
+impl<T> Pool<T> {
+    /// Get an item from the pool as a shared, read-only `Arc`.
+    ///
+    /// This suits read-heavy data like configuration snapshots which are
+    /// passed to APIs expecting an `Arc`. The item is returned to the pool
+    /// when the last clone of the `Arc` is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `get`.
+    #[track_caller]
+    pub fn get_arc(&self) -> Arc<PooledInner<T>> {
+        let pool = &self.internal;
+        let (item, hit) = pool.acquire(|| (*pool.create)()).expect("pool is empty");
+        let origin = pool.check_out(&item, hit);
+        Arc::new(PooledInner {
+            item: Some(item),
+            pool: self.clone(),
+            origin,
+        })
+    }
+}
+
+impl<T> Deref for PooledInner<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.item.as_ref().unwrap()
+    }
+}
+
+impl<T> Drop for PooledInner<T> {
+    fn drop(&mut self) {
+        let pool = &self.pool.internal;
+        if let Some(item) = self.item.take() {
+            if let Some(stats) = &pool.stats {
+                stats.record_return();
+            }
+            self.pool.push(item);
+        }
+        pool.check_in(&self.origin);
+    }
+}
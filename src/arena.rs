@@ -0,0 +1,70 @@
+//! A pool which allocates its items from a bump arena.
+
+use bumpalo::Bump;
+use std::fmt::{self, Debug};
+
+/// A pool which allocates items from a `bumpalo::Bump` arena instead of
+/// allocating and recycling them individually.
+///
+/// Checking an item out only bumps a pointer, and `reset_arena` frees every
+/// item at once while keeping the arena's memory for the next round. This
+/// suits items which are created and discarded in cycles, like per-frame or
+/// per-request scratch data.
+///
+/// The items' destructors never run, so any memory or resources they own
+/// outside the arena are leaked. Like the arena, the pool can be sent to
+/// another thread but not shared between threads.
+pub struct ArenaPool<T> {
+    bump: Bump,
+    create: Box<dyn Fn() -> T + Send>,
+}
+
+impl<T> Debug for ArenaPool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArenaPool")
+            .field("allocated_bytes", &self.allocated_bytes())
+            .field("create", &"Box<dyn Fn() -> T + Send>")
+            .finish()
+    }
+}
+
+impl<T> ArenaPool<T> {
+    /// Create a new ArenaPool from an initializer function.
+    pub fn new<C>(create: C) -> ArenaPool<T>
+    where
+        C: Fn() -> T + Send + 'static,
+    {
+        ArenaPool::with_capacity(create, 0)
+    }
+
+    /// Create a new ArenaPool whose arena starts out with room for `bytes`
+    /// bytes.
+    pub fn with_capacity<C>(create: C, bytes: usize) -> ArenaPool<T>
+    where
+        C: Fn() -> T + Send + 'static,
+    {
+        ArenaPool {
+            bump: Bump::with_capacity(bytes),
+            create: Box::new(create),
+        }
+    }
+
+    /// Create a new item in the arena.
+    ///
+    /// The item lives until the next call to `reset_arena`.
+    pub fn get(&self) -> &mut T {
+        self.bump.alloc_with(|| (self.create)())
+    }
+
+    /// Free every item at once, keeping the arena's memory to allocate new
+    /// items from.
+    pub fn reset_arena(&mut self) {
+        self.bump.reset();
+    }
+
+    /// Returns the number of bytes the arena has reserved, including the
+    /// space which isn't in use.
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{Builder, ItemGuard, OnDrop, Pool};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::runtime::Handle;
+
+/// A boxed future returned by an asynchronous clear function.
+pub type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send + 'static>>;
+
+/// An asynchronous clear function, and a way to run it from `Drop`.
+pub(crate) struct AsyncClear<T> {
+    pub(crate) clear: Arc<dyn Fn(T) -> BoxFuture<T> + Send + Sync>,
+    pub(crate) spawn: Box<dyn Fn(Pool<T>, T) + Send + Sync>,
+}
+
+impl<T: Send + 'static> AsyncClear<T> {
+    fn new<D>(clear: D) -> Self
+    where
+        D: Fn(T) -> BoxFuture<T> + Send + Sync + 'static,
+    {
+        let clear = Arc::new(clear);
+        let handle = Handle::current();
+        let spawn_clear = clear.clone();
+        AsyncClear {
+            clear,
+            spawn: Box::new(move |pool, item| {
+                let clear = spawn_clear.clone();
+                handle.spawn(async move {
+                    let item = clear(item).await;
+                    pool.internal.store(item);
+                });
+            }),
+        }
+    }
+}
+
+impl<T: Send + 'static> Builder<T> {
+    /// Set an asynchronous function used to clear items before they're
+    /// returned to the pool. This replaces any synchronous clear function.
+    ///
+    /// Because `Drop` can't wait for a future, dropping an `ItemGuard` spawns
+    /// the clear function onto the Tokio runtime this is called from, and the
+    /// item is returned to the pool once it completes. If the runtime has shut
+    /// down by then the item is dropped instead. Use `ItemGuard::recycle` to
+    /// wait for the item to be returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime.
+    pub fn async_clear<D>(mut self, clear: D) -> Self
+    where
+        D: Fn(T) -> BoxFuture<T> + Send + Sync + 'static,
+    {
+        self.async_clear = Some(AsyncClear::new(clear));
+        self
+    }
+}
+
+impl<T: Send + 'static> Pool<T> {
+    /// Create a new Pool from an initializer function and an asynchronous
+    /// clear function.
+    ///
+    /// See `Builder::async_clear` for more details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime.
+    pub fn with_async_clear<C, D>(create: C, clear: D) -> Pool<T>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+        D: Fn(T) -> BoxFuture<T> + Send + Sync + 'static,
+    {
+        Pool::builder(create).async_clear(clear).build()
+    }
+}
+
+impl<'a, T: Send + 'static> ItemGuard<'a, T> {
+    /// Clear the item and return it to the pool, waiting for an asynchronous
+    /// clear function to complete.
+    ///
+    /// For pools without an asynchronous clear function this is the same as
+    /// dropping the guard.
+    pub async fn recycle(self) {
+        let pool = self.pool;
+        let async_clear = match &pool.internal.async_clear {
+            Some(async_clear) if self.on_drop == OnDrop::Recycle => async_clear,
+            // Dropping the guard already does everything `recycle` would.
+            _ => {
+                drop(self);
+                return;
+            }
+        };
+        let (item, check_in) = self.take_item();
+        if let Some(stats) = &pool.internal.stats {
+            stats.record_return();
+        }
+        let item = (async_clear.clear)(item).await;
+        pool.internal.store(item);
+        // Like dropping the guard, only check in once the item is stored.
+        drop(check_in);
+    }
+}
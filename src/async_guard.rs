@@ -0,0 +1,111 @@
+use crate::{origin::Origin, Pool};
+use std::fmt::{self, Debug};
+use std::ops::{Deref, DerefMut};
+
+/// An owned guard for holding pooled items in async tasks.
+///
+/// Unlike `ItemGuard`, this keeps its own handle to the pool, so it can be
+/// moved into spawned tasks and held across `.await` points.
+///
+/// # Cancel safety
+///
+/// Returning the item never blocks or awaits anything when the guard is
+/// dropped, so a task holding the guard can be cancelled at any point and the
+/// item still returns to the pool. With an asynchronous clear function the
+/// clear runs in a newly spawned task, exactly as for `ItemGuard`. Use
+/// `recycle` to return the item explicitly and wait for it to be cleared.
+pub struct AsyncItemGuard<T> {
+    item: Option<T>,
+    pool: Pool<T>,
+    origin: Origin,
+}
+
+impl<T: Debug> Debug for AsyncItemGuard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncItemGuard")
+            .field("item", &self.item)
+            .finish()
+    }
+}
+
+impl<T> Pool<T> {
+    /// Get an item from the pool in a guard which can be held across `.await`
+    /// points and moved between tasks.
+    ///
+    /// See `AsyncItemGuard`.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `get`.
+    #[track_caller]
+    pub fn get_async_guard(&self) -> AsyncItemGuard<T> {
+        let pool = &self.internal;
+        let (item, hit) = pool.acquire(|| (*pool.create)()).expect("pool is empty");
+        AsyncItemGuard::new(self, item, hit)
+    }
+}
+
+impl<T> AsyncItemGuard<T> {
+    /// Check out an item, which was either reused from the queue (a hit) or
+    /// newly created.
+    #[track_caller]
+    pub(crate) fn new(pool: &Pool<T>, item: T, hit: bool) -> Self {
+        let origin = pool.internal.check_out(&item, hit);
+        AsyncItemGuard {
+            item: Some(item),
+            pool: pool.clone(),
+            origin,
+        }
+    }
+}
+
+impl<T: Send + 'static> AsyncItemGuard<T> {
+    /// Clear the item and return it to the pool, waiting for an asynchronous
+    /// clear function to complete.
+    ///
+    /// For pools without an asynchronous clear function this is the same as
+    /// dropping the guard.
+    pub async fn recycle(mut self) {
+        let pool = self.pool.clone();
+        let item = self.item.take().unwrap();
+        if let Some(stats) = &pool.internal.stats {
+            stats.record_return();
+        }
+        match &pool.internal.async_clear {
+            Some(async_clear) => {
+                let item = (async_clear.clear)(item).await;
+                pool.internal.store(item);
+            }
+            None => pool.push(item),
+        }
+        // Like dropping the guard, only check in once the item is stored.
+        drop(self);
+    }
+}
+
+impl<T> Deref for AsyncItemGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.item.as_ref().unwrap()
+    }
+}
+
+impl<T> DerefMut for AsyncItemGuard<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.item.as_mut().unwrap()
+    }
+}
+
+impl<T> Drop for AsyncItemGuard<T> {
+    fn drop(&mut self) {
+        let pool = &self.pool.internal;
+        if let Some(item) = self.item.take() {
+            if let Some(stats) = &pool.stats {
+                stats.record_return();
+            }
+            self.pool.push(item);
+        }
+        pool.check_in(&self.origin);
+    }
+}
@@ -0,0 +1,9 @@
+//! Atomics used for the pool's counters.
+//!
+//! With the `portable-atomic` feature these come from the `portable-atomic`
+//! crate, which supports targets without native atomics of every width.
+
+#[cfg(feature = "portable-atomic")]
+pub(crate) use portable_atomic::{fence, AtomicU64, AtomicUsize, Ordering};
+#[cfg(not(feature = "portable-atomic"))]
+pub(crate) use std::sync::atomic::{fence, AtomicU64, AtomicUsize, Ordering};
@@ -0,0 +1,40 @@
+use crate::atomic::{AtomicUsize, Ordering};
+
+/// Keeps an exponential moving average of how much of returned items was
+/// used, and reserves that much in reused items.
+pub(crate) struct Average<T> {
+    len: fn(&T) -> usize,
+    reserve: fn(&mut T, usize),
+    /// The average times 8, so steps smaller than a whole byte aren't lost.
+    value: AtomicUsize,
+}
+
+impl<T> Average<T> {
+    pub(crate) fn new(len: fn(&T) -> usize, reserve: fn(&mut T, usize)) -> Average<T> {
+        Average {
+            len,
+            reserve,
+            value: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the current average.
+    pub(crate) fn get(&self) -> usize {
+        self.value.load(Ordering::Relaxed) >> 3
+    }
+
+    /// Add a returned item to the average, giving it a weight of 1/8.
+    pub(crate) fn record(&self, item: &T) {
+        let len = (self.len)(item);
+        let _ = self
+            .value
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |scaled| {
+                Some((scaled - (scaled >> 3)).saturating_add(len))
+            });
+    }
+
+    /// Reserve room for the average in a reused item.
+    pub(crate) fn apply(&self, item: &mut T) {
+        (self.reserve)(item, self.get());
+    }
+}
@@ -0,0 +1,109 @@
+//! Guards which let a producer wait until a consumer is done with an item.
+
+use crate::{AsyncItemGuard, Pool};
+use std::fmt::{self, Debug};
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::oneshot;
+
+/// A future which resolves once the item checked out with it has been
+/// processed and returned to the pool.
+///
+/// `Pool::get_awaitable` splits a checkout into two halves:
+///
+/// 1. The producer keeps the `AwaitableGuard`, fills the item through the
+///    `Completion`, and hands the `Completion` to the consumer, for example
+///    over a channel.
+/// 2. The consumer processes the item and calls `Completion::complete`, which
+///    returns the item to the pool before signalling the producer.
+/// 3. The producer awaits the `AwaitableGuard`, which resolves once the
+///    signal arrives. By then the item is back in the pool, so the producer
+///    can check it out again, unless the pool has an asynchronous clear
+///    function or batched returns: the item may then still be being cleared,
+///    or staged on the consumer's thread.
+///
+/// Dropping the `Completion` also returns the item and resolves the guard, so
+/// the producer isn't left waiting if the consumer goes away.
+#[derive(Debug)]
+pub struct AwaitableGuard {
+    done: oneshot::Receiver<()>,
+}
+
+/// The half of a checkout made with `Pool::get_awaitable` which holds the
+/// item.
+///
+/// See `AwaitableGuard`.
+pub struct Completion<T> {
+    // Declared before `done` so the item is returned before the producer is
+    // signalled, even when the completion is just dropped.
+    item: AsyncItemGuard<T>,
+    done: oneshot::Sender<()>,
+}
+
+impl<T: Debug> Debug for Completion<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Completion")
+            .field("item", &*self.item)
+            .finish()
+    }
+}
+
+impl<T> Pool<T> {
+    /// Get an item from the pool along with a future which resolves once the
+    /// item has been processed and returned.
+    ///
+    /// See `AwaitableGuard` for how the two halves are used.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `get`.
+    #[track_caller]
+    pub fn get_awaitable(&self) -> (AwaitableGuard, Completion<T>) {
+        let (done, receiver) = oneshot::channel();
+        let completion = Completion {
+            item: self.get_async_guard(),
+            done,
+        };
+        (AwaitableGuard { done: receiver }, completion)
+    }
+}
+
+impl<T> Completion<T> {
+    /// Return the item to the pool, then resolve the matching
+    /// `AwaitableGuard`.
+    ///
+    /// With `Builder::async_clear` the guard resolves once the clear has been
+    /// spawned, not once it's done.
+    pub fn complete(self) {
+        let Completion { item, done } = self;
+        drop(item);
+        // The producer may have stopped waiting, which is fine.
+        let _ = done.send(());
+    }
+}
+
+impl<T> Deref for Completion<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.item
+    }
+}
+
+impl<T> DerefMut for Completion<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.item
+    }
+}
+
+impl Future for AwaitableGuard {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        // An error means the completion was dropped, which also returns the
+        // item.
+        Pin::new(&mut self.done).poll(cx).map(|_| ())
+    }
+}
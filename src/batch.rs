@@ -0,0 +1,167 @@
+//! Returning items to a pool in batches from each thread.
+//!
+//! Items returned to a pool with batched returns are staged in a buffer local
+//! to the returning thread, and only pushed to the pool's queue once the
+//! buffer is full, once `Pool::flush_returns` is called, or once the thread
+//! exits.
+
+use crate::{Builder, Internal, Pool};
+use std::any::Any;
+use std::cell::RefCell;
+use std::mem;
+use std::sync::{Arc, Weak};
+
+/// Stages an item which was cleared and is about to be stored.
+pub(crate) type Stage<T> = Box<dyn Fn(&Pool<T>, T) + Send + Sync>;
+
+/// The items a thread has staged for a single pool.
+struct Batch<T> {
+    pool: Weak<Internal<T>>,
+    items: Vec<T>,
+    size: usize,
+}
+
+/// A `Batch` of any item type.
+trait Staged {
+    /// Returns whether the items are staged for the pool at `pool`.
+    fn is_for(&self, pool: *const ()) -> bool;
+    /// Returns whether the pool has been dropped.
+    fn is_dead(&self) -> bool;
+    /// Store the staged items in the pool.
+    fn flush(&mut self);
+    fn as_any(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Send + 'static> Staged for Batch<T> {
+    fn is_for(&self, pool: *const ()) -> bool {
+        self.pool.as_ptr() as *const () == pool
+    }
+
+    fn is_dead(&self) -> bool {
+        self.pool.strong_count() == 0
+    }
+
+    fn flush(&mut self) {
+        let items = mem::take(&mut self.items);
+        if let Some(pool) = self.pool.upgrade() {
+            let mut stored = false;
+            for item in items {
+                stored |= pool.enqueue(item);
+            }
+            if stored {
+                pool.notify(true);
+            }
+        }
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Every batch staged by a thread, which are flushed when the thread exits.
+struct Batches(Vec<Box<dyn Staged>>);
+
+impl Drop for Batches {
+    fn drop(&mut self) {
+        self.0.iter_mut().for_each(|batch| batch.flush());
+    }
+}
+
+thread_local! {
+    static BATCHES: RefCell<Batches> = RefCell::new(Batches(Vec::new()));
+}
+
+/// Run `f` on the calling thread's batch for `pool`. If there's none yet, one
+/// holding up to `size` items is created, unless `size` is `None`.
+///
+/// Returns `None` if there's no batch, or if the batches can't be accessed,
+/// which happens while the thread is exiting, or while a batch is being
+/// flushed if an item's `Drop` returns another item.
+fn with_batch<T, F, R>(pool: &Pool<T>, size: Option<usize>, f: F) -> Option<R>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut Batch<T>) -> R,
+{
+    let id = Arc::as_ptr(&pool.internal) as *const ();
+    BATCHES
+        .try_with(|batches| {
+            let mut batches = batches.try_borrow_mut().ok()?;
+            let batches = &mut batches.0;
+            batches.retain(|batch| !batch.is_dead());
+            let index = match batches.iter().position(|batch| batch.is_for(id)) {
+                Some(index) => index,
+                None => {
+                    let size = size?;
+                    batches.push(Box::new(Batch {
+                        pool: Arc::downgrade(&pool.internal),
+                        items: Vec::with_capacity(size),
+                        size,
+                    }));
+                    batches.len() - 1
+                }
+            };
+            let batch = batches[index].as_any().downcast_mut::<Batch<T>>()?;
+            Some(f(batch))
+        })
+        .ok()
+        .flatten()
+}
+
+impl<T: Send + 'static> Builder<T> {
+    /// Stage returned items in a buffer local to the returning thread, and
+    /// push them to the pool's queue `batch` at a time.
+    ///
+    /// This cuts down on traffic to the shared queue when a thread returns
+    /// many items in quick succession. Staged items can't be checked out by
+    /// any thread, including the one which staged them, until they're
+    /// flushed: once `batch` items are staged, by `Pool::flush_returns`, or
+    /// when the thread exits. Items returned without clearing, like those of
+    /// `Pool::get_ref_uncleared`, aren't batched.
+    pub fn batched_return(mut self, batch: usize) -> Self {
+        self.stage = Some(Box::new(move |pool, item| {
+            let mut item = Some(item);
+            let full = with_batch(pool, Some(batch), |staged| {
+                staged.items.extend(item.take());
+                if staged.items.len() >= staged.size {
+                    staged.flush();
+                }
+            });
+            if let (None, Some(item)) = (full, item) {
+                pool.internal.store(item);
+            }
+        }));
+        self
+    }
+}
+
+impl<T: Send + 'static> Pool<T> {
+    /// Create a new Pool from an initializer function, whose returned items
+    /// are pushed to the queue `batch` at a time.
+    ///
+    /// See `Builder::batched_return`.
+    pub fn batched_return<C>(create: C, batch: usize) -> Pool<T>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+    {
+        Pool::builder(create).batched_return(batch).build()
+    }
+
+    /// Push the items the calling thread has staged for this pool to the
+    /// queue.
+    ///
+    /// See `Builder::batched_return`. This does nothing for pools without
+    /// batched returns.
+    pub fn flush_returns(&self) {
+        with_batch(self, None, |staged: &mut Batch<T>| staged.flush());
+    }
+
+    /// Push the items the calling thread has staged for this pool to the
+    /// queue, before the thread exits.
+    ///
+    /// This is the same as `flush_returns`. Staged items are also flushed
+    /// when the thread exits, unless the pool was dropped first.
+    pub fn flush_current_thread_cache(&self) {
+        self.flush_returns();
+    }
+}
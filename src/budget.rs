@@ -0,0 +1,43 @@
+use crate::atomic::{AtomicUsize, Ordering};
+use crate::Pool;
+
+/// Counts the allocations a pool makes on checkout against a limit.
+///
+/// The window is whatever the user decides: it only ends when
+/// `Pool::reset_alloc_budget` is called.
+pub(crate) struct Budget {
+    limit: usize,
+    allocations: AtomicUsize,
+    on_exceeded: Box<dyn Fn(usize) + Send + Sync>,
+}
+
+impl Budget {
+    pub(crate) fn new(limit: usize, on_exceeded: Box<dyn Fn(usize) + Send + Sync>) -> Self {
+        Budget {
+            limit,
+            allocations: AtomicUsize::new(0),
+            on_exceeded,
+        }
+    }
+
+    /// Record a checkout which created a new item.
+    pub(crate) fn record(&self) {
+        let allocations = self.allocations.fetch_add(1, Ordering::Relaxed) + 1;
+        if allocations > self.limit {
+            (self.on_exceeded)(allocations);
+        }
+    }
+}
+
+impl<T> Pool<T> {
+    /// Start a new allocation budget window, returning the number of
+    /// allocations made in the previous one.
+    ///
+    /// Returns 0 if the pool has no budget. See `Builder::alloc_budget`.
+    pub fn reset_alloc_budget(&self) -> usize {
+        match &self.internal.budget {
+            Some(budget) => budget.allocations.swap(0, Ordering::Relaxed),
+            None => 0,
+        }
+    }
+}
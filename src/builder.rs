@@ -0,0 +1,369 @@
+use crate::adaptive::{Adaptive, AdaptivePolicy};
+use crate::atomic::{AtomicU64, AtomicUsize};
+use crate::average::Average;
+use crate::batch::Stage;
+use crate::budget::Budget;
+use crate::origin::{MaxHold, Origins};
+use crate::store::{SizeOf, Store, Weigh};
+use crate::{Clear, Grow, Internal, Pool, StatsSink, Visit};
+use arc_swap::ArcSwap;
+use std::fmt::{self, Debug};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+/// What a pool does when an item is requested while the pool is empty.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GrowthPolicy {
+    /// Create a new item using the pool's initializer function.
+    #[default]
+    AlwaysAllocate,
+    /// Don't create a new item. `Pool::try_get` returns `None` instead.
+    FailOnEmpty,
+    /// Wait until another handle returns an item to the pool.
+    ///
+    /// Note that a pool which has never been filled will wait forever, so use
+    /// `Pool::prefill` to seed it first.
+    Block,
+}
+
+/// Configure and create a new `Pool`.
+pub struct Builder<T> {
+    pub(crate) create: Arc<dyn Fn() -> T + Send + Sync>,
+    pub(crate) clear: Clear<T>,
+    clear_owned: Option<Box<dyn Fn(T) -> T + Send + Sync>>,
+    create_sized: Option<Box<dyn Fn(usize) -> T + Send + Sync>>,
+    grow: Option<Grow<T>>,
+    #[cfg(feature = "tokio")]
+    pub(crate) async_clear: Option<crate::async_clear::AsyncClear<T>>,
+    growth_policy: GrowthPolicy,
+    capacity: usize,
+    spin_tries: usize,
+    warm_on_first_get: usize,
+    warmup_wait: Option<Duration>,
+    adaptive: Option<AdaptivePolicy>,
+    on_get: Option<Box<dyn Fn(bool) + Send + Sync>>,
+    on_pool_drop: Option<Visit<T>>,
+    pub(crate) spill: Option<Pool<T>>,
+    dedup: Option<fn(&T, &T) -> bool>,
+    identity: Option<fn(&T) -> usize>,
+    pub(crate) average: Option<Average<T>>,
+    pub(crate) stage: Option<Stage<T>>,
+    stats: Option<Arc<dyn StatsSink>>,
+    budget: Option<Budget>,
+    max_hold: Option<MaxHold>,
+    size_of: Option<SizeOf<T>>,
+    pub(crate) weigh: Option<Weigh<T>>,
+}
+
+impl<T: PartialEq> Builder<T> {
+    /// Drop returned items which are equal to an item already stored in the
+    /// pool.
+    ///
+    /// This compares each returned item against every stored item, briefly
+    /// taking them all out of the queue to do so, which makes returns O(n) and
+    /// serializes them. Only use it for small pools, ideally with a small
+    /// `capacity`.
+    pub fn dedup(mut self) -> Self {
+        self.dedup = Some(T::eq);
+        self
+    }
+}
+
+impl<T> Debug for Builder<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Builder")
+            .field("create", &"Box<dyn Fn() -> T + Send + Sync>")
+            .field("clear", &"Box<dyn Fn(&mut T) + Send + Sync>")
+            .field("growth_policy", &self.growth_policy)
+            .field("capacity", &self.capacity)
+            .field("spin_tries", &self.spin_tries)
+            .field("adaptive", &self.adaptive)
+            .finish()
+    }
+}
+
+impl<T> Builder<T> {
+    pub(crate) fn new<C>(create: C) -> Builder<T>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+    {
+        Builder::with_shared(Arc::new(create), Arc::new(|_| {}))
+    }
+
+    /// Create a `Builder` with create and clear functions which are shared
+    /// with another pool.
+    pub(crate) fn with_shared(
+        create: Arc<dyn Fn() -> T + Send + Sync>,
+        clear: Clear<T>,
+    ) -> Builder<T> {
+        Builder {
+            create,
+            clear,
+            clear_owned: None,
+            create_sized: None,
+            grow: None,
+            #[cfg(feature = "tokio")]
+            async_clear: None,
+            growth_policy: GrowthPolicy::default(),
+            capacity: usize::MAX,
+            spin_tries: 0,
+            warm_on_first_get: 0,
+            warmup_wait: None,
+            adaptive: None,
+            on_get: None,
+            on_pool_drop: None,
+            spill: None,
+            dedup: None,
+            identity: None,
+            average: None,
+            stage: None,
+            stats: None,
+            budget: None,
+            max_hold: None,
+            size_of: None,
+            weigh: None,
+        }
+    }
+
+    /// Set the function used to clear items before they're returned to the
+    /// pool.
+    ///
+    /// See `Pool::with_clear` for more details.
+    pub fn clear<D>(mut self, clear: D) -> Self
+    where
+        D: Fn(&mut T) + Send + Sync + 'static,
+    {
+        self.clear = Arc::new(clear);
+        self
+    }
+
+    /// Set a function which takes ownership of items before they're returned to
+    /// the pool, and returns the item to store.
+    ///
+    /// Unlike `clear`, this can replace an item entirely, for example with a
+    /// fresh one once the old one has grown too large. It runs after the
+    /// function set by `clear`.
+    pub fn clear_owned<D>(mut self, clear: D) -> Self
+    where
+        D: Fn(T) -> T + Send + Sync + 'static,
+    {
+        self.clear_owned = Some(Box::new(clear));
+        self
+    }
+
+    /// Set the function used to create new items for `Pool::get_sized`, which
+    /// receives the requested size.
+    pub fn create_sized<C>(mut self, create: C) -> Self
+    where
+        C: Fn(usize) -> T + Send + Sync + 'static,
+    {
+        self.create_sized = Some(Box::new(create));
+        self
+    }
+
+    /// Set the function used to grow reused items to the size requested from
+    /// `Pool::get_sized`.
+    pub fn grow<G>(mut self, grow: G) -> Self
+    where
+        G: Fn(&mut T, usize) + Send + Sync + 'static,
+    {
+        self.grow = Some(Box::new(grow));
+        self
+    }
+
+    /// Set what the pool does when it's empty.
+    ///
+    /// Defaults to `GrowthPolicy::AlwaysAllocate`.
+    pub fn growth_policy(mut self, policy: GrowthPolicy) -> Self {
+        self.growth_policy = policy;
+        self
+    }
+
+    /// Set the maximum number of items the pool stores.
+    ///
+    /// Items returned to a full pool are dropped. Defaults to no limit. This
+    /// can be changed later with `Pool::set_capacity`.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Set how many extra times `get` checks an empty pool before falling back
+    /// to the growth policy.
+    ///
+    /// Under heavy contention another thread is often about to return an item,
+    /// so spinning briefly can trade a little CPU time for fewer allocations.
+    /// Defaults to 0.
+    pub fn spin_tries(mut self, tries: usize) -> Self {
+        self.spin_tries = tries;
+        self
+    }
+
+    /// Create `count` extra items and store them in the pool on the first
+    /// checkout.
+    ///
+    /// This makes the first `get` slower in exchange for fewer misses
+    /// afterwards, without paying for the items up front like `prefill` does.
+    /// Items beyond the pool's capacity aren't created.
+    pub fn warm_on_first_get(mut self, count: usize) -> Self {
+        self.warm_on_first_get = count;
+        self
+    }
+
+    /// Wait up to `timeout` for an item to be returned before creating a new
+    /// one when the pool is empty.
+    ///
+    /// When a cold pool is hit by a sudden burst, every thread misses and
+    /// allocates at once. Waiting briefly lets the items checked out by the
+    /// first threads satisfy the rest, at the cost of latency on misses. The
+    /// pool only waits while some items are checked out, since otherwise none
+    /// could be returned in time. This only applies to
+    /// `GrowthPolicy::AlwaysAllocate`.
+    pub fn warmup_wait(mut self, timeout: Duration) -> Self {
+        self.warmup_wait = Some(timeout);
+        self
+    }
+
+    /// Limit how many items the pool keeps based on how many are in use.
+    ///
+    /// This applies on top of the fixed capacity, to items returned to the
+    /// pool once it has seen a `get` or drop; before that there's no peak to
+    /// go by. Items created by `Pool::prefill` and `warm_on_first_get` are
+    /// always stored, and are shed as they're returned if the pool holds more
+    /// than it needs. See `AdaptivePolicy`.
+    pub fn adaptive(mut self, policy: AdaptivePolicy) -> Self {
+        self.adaptive = Some(policy);
+        self
+    }
+
+    /// Set a function called whenever an item is checked out of the pool.
+    ///
+    /// It receives `true` if the item was reused, and `false` if it was newly
+    /// created. This is a lightweight hook for recording metrics.
+    pub fn on_get<F>(mut self, on_get: F) -> Self
+    where
+        F: Fn(bool) + Send + Sync + 'static,
+    {
+        self.on_get = Some(Box::new(on_get));
+        self
+    }
+
+    /// Set a function which runs over every item still stored in the pool
+    /// when the pool itself is dropped.
+    ///
+    /// This runs once the last handle to the pool is gone, for example to
+    /// flush buffers or release external resources. Items which are checked
+    /// out at that point aren't affected; they're dropped along with their
+    /// guards.
+    pub fn on_pool_drop<F>(mut self, on_pool_drop: F) -> Self
+    where
+        F: Fn(&mut T) + Send + Sync + 'static,
+    {
+        self.on_pool_drop = Some(Box::new(on_pool_drop));
+        self
+    }
+
+    /// Report every checkout and return to `sink`.
+    ///
+    /// This decouples the pool from any particular metrics crate. See
+    /// `StatsSink`.
+    pub fn collect_stats_into(mut self, sink: Arc<dyn StatsSink>) -> Self {
+        self.stats = Some(sink);
+        self
+    }
+
+    /// Call `on_exceeded` whenever a checkout allocates more than `limit` new
+    /// items in the current window.
+    ///
+    /// The checkout still goes ahead; this is only a signal, for example to
+    /// raise an alert. `on_exceeded` receives the number of allocations made in
+    /// the window so far. Start a new window with `Pool::reset_alloc_budget`.
+    pub fn alloc_budget<F>(mut self, limit: usize, on_exceeded: F) -> Self
+    where
+        F: Fn(usize) + Send + Sync + 'static,
+    {
+        self.budget = Some(Budget::new(limit, Box::new(on_exceeded)));
+        self
+    }
+
+    /// Call `warn` with how long a guard was held whenever one is dropped after
+    /// being held for longer than `limit`.
+    ///
+    /// Holding on to an item for too long is often a sign of a bug. This only
+    /// has an effect in debug builds, so release builds don't pay for timing
+    /// every checkout.
+    pub fn max_hold<F>(mut self, limit: Duration, warn: F) -> Self
+    where
+        F: Fn(Duration) + Send + Sync + 'static,
+    {
+        self.max_hold = Some(MaxHold::new(limit, Box::new(warn)));
+        self
+    }
+
+    /// Keep stored items ordered by `size_of`, so `get` always returns the
+    /// largest one.
+    ///
+    /// See `Pool::with_size_priority` for more details.
+    pub fn size_priority<S>(mut self, size_of: S) -> Self
+    where
+        S: Fn(&T) -> usize + Send + Sync + 'static,
+    {
+        self.size_of = Some(Box::new(size_of));
+        self
+    }
+
+    /// Panic when an item is checked out while it's already checked out, as
+    /// told apart by `identity`.
+    ///
+    /// Items can only be issued twice when they're handles to something
+    /// shared, like an `Arc` or a file descriptor, and a copy of one was
+    /// returned to the pool while the original is still in use. `identity`
+    /// should return the same value for both, like the address the `Arc`
+    /// points to. An item replaced with `ItemGuard::swap` isn't tracked. This
+    /// only has an effect in debug builds.
+    pub fn check_double_issue(mut self, identity: fn(&T) -> usize) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Create the `Pool`.
+    pub fn build(self) -> Pool<T> {
+        Pool {
+            internal: Arc::new(Internal {
+                queue: Store::new(self.size_of, self.weigh),
+                create: self.create,
+                clear: ArcSwap::from_pointee(self.clear),
+                clear_owned: self.clear_owned,
+                create_sized: self.create_sized,
+                grow: self.grow,
+                #[cfg(feature = "tokio")]
+                async_clear: self.async_clear,
+                growth_policy: self.growth_policy,
+                capacity: AtomicUsize::new(self.capacity),
+                spin_tries: self.spin_tries,
+                warm: AtomicUsize::new(self.warm_on_first_get),
+                warmup_wait: self.warmup_wait,
+                waiters: AtomicUsize::new(0),
+                lock: Mutex::new(()),
+                returned: Condvar::new(),
+                #[cfg(any(feature = "stream", feature = "tower"))]
+                wakers: Mutex::new(Vec::new()),
+                in_use: AtomicUsize::new(0),
+                next_tag: AtomicU64::new(0),
+                miss_sizes: Mutex::new(Vec::new()),
+                adaptive: self.adaptive.map(Adaptive::new),
+                on_get: self.on_get,
+                on_pool_drop: self.on_pool_drop,
+                spill: self.spill,
+                dedup: self.dedup,
+                identity: self.identity,
+                average: self.average,
+                stage: self.stage,
+                stats: self.stats,
+                budget: self.budget,
+                origins: Origins::new(),
+                max_hold: self.max_hold,
+            }),
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use crate::average::Average;
+use crate::{Builder, ItemGuard, Pool};
+use std::hint;
+use std::io::IoSlice;
+use std::iter;
+use std::mem::MaybeUninit;
+use std::slice;
+use std::sync::Arc;
+
+impl<'a> ItemGuard<'a, Vec<u8>> {
+    /// Divide the buffer into two mutable halves at `mid`.
+    ///
+    /// The first half contains bytes `[0, mid)` and the second `[mid, len)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > len`.
+    pub fn split_at_mut(&mut self, mid: usize) -> (&mut [u8], &mut [u8]) {
+        let len = self.len();
+        assert!(
+            mid <= len,
+            "split point {} is out of bounds for a buffer of length {}",
+            mid,
+            len
+        );
+        self.as_mut_slice().split_at_mut(mid)
+    }
+
+    /// Divide the buffer into non-overlapping mutable chunks of `chunk` bytes
+    /// each, for protocols with fixed-size frames.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk` is zero or the buffer's length isn't a multiple of
+    /// `chunk`.
+    pub fn chunks_mut_exact(&mut self, chunk: usize) -> impl Iterator<Item = &mut [u8]> + '_ {
+        let len = self.len();
+        assert!(
+            chunk != 0 && len % chunk == 0,
+            "a buffer of length {} can't be split into chunks of {} bytes",
+            len,
+            chunk
+        );
+        self.as_mut_slice().chunks_exact_mut(chunk)
+    }
+
+    /// Divide the buffer's contents at each of `offsets` into slices for
+    /// vectored writes, without copying.
+    ///
+    /// The slices cover `[0, offsets[0])`, `[offsets[0], offsets[1])`, and so
+    /// on up to `[offsets[n - 1], len)`, so there's one more slice than there
+    /// are offsets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offsets aren't in ascending order or any of them is
+    /// greater than `len`.
+    pub fn io_slices(&self, offsets: &[usize]) -> Vec<IoSlice<'_>> {
+        let len = self.len();
+        let mut slices = Vec::with_capacity(offsets.len() + 1);
+        let mut start = 0;
+        for &offset in offsets.iter().chain(iter::once(&len)) {
+            assert!(
+                start <= offset && offset <= len,
+                "offset {} is out of order or out of bounds for a buffer of length {}",
+                offset,
+                len
+            );
+            slices.push(IoSlice::new(&self[start..offset]));
+            start = offset;
+        }
+        slices
+    }
+
+    /// Returns the buffer's length and capacity, for reporting how much of
+    /// the pooled allocation is in use.
+    pub fn usage(&self) -> (usize, usize) {
+        (self.len(), self.capacity())
+    }
+}
+
+/// The granularity at which the OS commits memory on most platforms.
+const PAGE_SIZE: usize = 4096;
+
+/// The byte written over returned buffers by `Builder::poison_on_return`.
+const POISON: u8 = 0xDB;
+
+impl Builder<Vec<u8>> {
+    /// Touch every page of newly created buffers before handing them out.
+    ///
+    /// The OS usually only commits memory once it's first written to, so the
+    /// first use of a fresh buffer can cause a page fault per page. This moves
+    /// that cost into the create path. The buffer's contents and length are
+    /// left unchanged.
+    pub fn prefault(mut self) -> Self {
+        let create = self.create;
+        self.create = Arc::new(move || {
+            let mut buf = create();
+            let (len, capacity) = (buf.len(), buf.capacity());
+            let last = capacity.checked_sub(1);
+            for i in (0..capacity).step_by(PAGE_SIZE).chain(last) {
+                if i < len {
+                    buf[i] = hint::black_box(buf[i]);
+                } else {
+                    buf.spare_capacity_mut()[i - len] = MaybeUninit::new(0);
+                }
+            }
+            buf
+        });
+        self
+    }
+
+    /// Keep a running total of the capacity of the stored buffers, which is
+    /// reported by `Pool::pooled_bytes`.
+    ///
+    /// The total is updated whenever a buffer is stored or taken out, so
+    /// reading it doesn't scan the pool.
+    pub fn track_pooled_bytes(mut self) -> Self {
+        self.weigh = Some(Vec::capacity);
+        self
+    }
+
+    /// Reserve room in reused buffers for the average length of returned
+    /// buffers, so callers rarely have to grow them.
+    ///
+    /// The average is an exponential moving average of the buffers' lengths
+    /// when they're returned, before they're cleared, with each return
+    /// weighing 1/8. Newly created buffers are left as they are.
+    pub fn reserve_average(mut self) -> Self {
+        self.average = Some(Average::new(Vec::len, Vec::reserve));
+        self
+    }
+
+    /// Overwrite the unused capacity of returned buffers with `0xDB` in debug
+    /// builds.
+    ///
+    /// Code which keeps reading through a pointer into a buffer after its
+    /// guard is dropped otherwise sees plausible stale data. With the usual
+    /// clear function of `Vec::clear`, the whole allocation is overwritten,
+    /// and the next user's writes replace the poison as usual. This wraps the
+    /// clear function set so far, so call it after `clear`. Release builds
+    /// don't poison anything.
+    pub fn poison_on_return(mut self) -> Self {
+        if cfg!(debug_assertions) {
+            let clear = self.clear;
+            self.clear = Arc::new(move |buf| {
+                clear(buf);
+                let len = buf.len();
+                buf.resize(buf.capacity(), POISON);
+                buf.truncate(len);
+            });
+        }
+        self
+    }
+
+    /// Shrink buffers whose capacity exceeds `target` when they're returned to
+    /// the pool.
+    ///
+    /// A single oversized request otherwise pins its allocation in the pool
+    /// for good. Shrinking keeps the buffer around instead of dropping it.
+    /// This wraps the clear function set so far, so call it after `clear`.
+    pub fn shrink_to_on_return(mut self, target: usize) -> Self {
+        let clear = self.clear;
+        self.clear = Arc::new(move |buf| {
+            clear(buf);
+            if buf.capacity() > target {
+                buf.shrink_to(target);
+            }
+        });
+        self
+    }
+}
+
+impl Pool<Vec<u8>> {
+    /// Returns the total capacity of the buffers stored in the pool, in bytes.
+    ///
+    /// Buffers which are checked out aren't counted. This is only tracked for
+    /// pools built with `Builder::track_pooled_bytes`, and is always 0 for
+    /// other pools. Buffers pushed through `raw_queue` aren't counted either.
+    pub fn pooled_bytes(&self) -> usize {
+        self.internal.queue.bytes().unwrap_or(0)
+    }
+
+    /// Get a buffer from the pool with all of its bytes set to zero.
+    ///
+    /// Buffers are zeroed up to their length on checkout, so the cost is only
+    /// paid when it's needed. This includes newly created buffers, whatever
+    /// the create function put in them.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `get`.
+    #[track_caller]
+    pub fn get_zeroed<'a>(&'a self) -> ItemGuard<'a, Vec<u8>> {
+        let mut guard = self.get();
+        guard.fill(0);
+        guard
+    }
+
+    /// Get a buffer from the pool with room for at least `capacity` bytes.
+    ///
+    /// Misses create a buffer of exactly `capacity` bytes instead of calling
+    /// the create function, and are recorded in `size_histogram`. Reused
+    /// buffers are grown if they're too small.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `get`.
+    #[track_caller]
+    pub fn get_with_capacity<'a>(&'a self, capacity: usize) -> ItemGuard<'a, Vec<u8>> {
+        let mut created = false;
+        let mut guard = self
+            .try_get_with(|| {
+                created = true;
+                Vec::with_capacity(capacity)
+            })
+            .expect("pool is empty");
+        if created {
+            // Bucket 0 counts empty requests, and bucket `n` the ones which round
+            // up to `1 << (n - 1)`. Sizes too large to round up share the top
+            // bucket.
+            let bucket = match capacity {
+                0 => 0,
+                _ => match capacity.checked_next_power_of_two() {
+                    Some(size) => size.trailing_zeros() as usize + 1,
+                    None => usize::BITS as usize,
+                },
+            };
+            let mut sizes = self.internal.miss_sizes.lock().unwrap();
+            if sizes.len() <= bucket {
+                sizes.resize(bucket + 1, 0);
+            }
+            sizes[bucket] += 1;
+        } else {
+            let len = guard.len();
+            guard.reserve(capacity.saturating_sub(len));
+        }
+        guard
+    }
+
+    /// Returns how many misses of `get_with_capacity` requested each size.
+    ///
+    /// Sizes are rounded up to the next power of two, and each entry is that
+    /// power of two along with its count. Requests for 0 bytes are counted
+    /// under 0, and sizes above the largest power of two a `usize` can hold
+    /// are counted under that power of two. Sizes which were never requested
+    /// are left out.
+    pub fn size_histogram(&self) -> Vec<(usize, u64)> {
+        let sizes = self.internal.miss_sizes.lock().unwrap();
+        sizes
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(bucket, &count)| match bucket {
+                0 => (0, count),
+                _ => (1 << (bucket - 1), count),
+            })
+            .collect()
+    }
+}
+
+impl Pool<Box<[MaybeUninit<u8>]>> {
+    /// Create a new Pool of `len` byte buffers which are left uninitialized.
+    ///
+    /// This avoids zeroing buffers which are overwritten immediately anyway.
+    /// Use `ItemGuard::assume_init_mut` to access the bytes once they've all
+    /// been written.
+    pub fn uninit_bytes(len: usize) -> Pool<Box<[MaybeUninit<u8>]>> {
+        Pool::new(move || iter::repeat_with(MaybeUninit::uninit).take(len).collect())
+    }
+}
+
+impl<'a> ItemGuard<'a, Box<[MaybeUninit<u8>]>> {
+    /// Access the buffer as initialized bytes.
+    ///
+    /// # Safety
+    ///
+    /// Every byte of the buffer must have been initialized. Buffers are
+    /// returned to the pool as-is, so bytes written by a previous user remain
+    /// initialized, but a fresh buffer from `Pool::uninit_bytes` contains no
+    /// initialized bytes at all.
+    #[allow(unsafe_code)]
+    pub unsafe fn assume_init_mut(&mut self) -> &mut [u8] {
+        let len = self.len();
+        // SAFETY: `MaybeUninit<u8>` has the same layout as `u8`, and the caller
+        // guarantees every byte is initialized.
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr() as *mut u8, len) }
+    }
+}
+
+impl Pool<String> {
+    /// Create a new Pool of strings with at least `capacity` bytes allocated.
+    ///
+    /// Strings are cleared before they're returned to the pool, which keeps
+    /// their allocation.
+    pub fn string(capacity: usize) -> Pool<String> {
+        Pool::with_clear(move || String::with_capacity(capacity), String::clear)
+    }
+}
+
+impl Pool<Vec<Vec<u8>>> {
+    /// Create a new Pool of `rows` byte buffers, each with at least `cols`
+    /// bytes allocated.
+    ///
+    /// Only the inner buffers are cleared before they're returned to the pool.
+    /// Clearing the outer `Vec` would drop the inner buffers along with their
+    /// allocations; this keeps all of them. Rows added or removed by users are
+    /// kept as-is.
+    pub fn nested_bytes(rows: usize, cols: usize) -> Pool<Vec<Vec<u8>>> {
+        Pool::with_clear(
+            move || (0..rows).map(|_| Vec::with_capacity(cols)).collect(),
+            |rows: &mut Vec<Vec<u8>>| rows.iter_mut().for_each(Vec::clear),
+        )
+    }
+}
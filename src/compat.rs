@@ -0,0 +1,48 @@
+//! Names matching the [`object-pool`](https://docs.rs/object-pool) crate.
+//!
+//! These ease migrating from `object-pool` to `remem`: import this module's
+//! contents and most code using `pull`, `try_pull`, `attach` and `Reusable`
+//! keeps working.
+//!
+//! ```rust
+//! use remem::compat::{PoolCompat, Reusable};
+//! use remem::Pool;
+//!
+//! let pool = Pool::new(Vec::<u8>::new);
+//! let item: Reusable<'_, Vec<u8>> = pool.pull(|| Vec::with_capacity(16));
+//! drop(item);
+//! ```
+
+use crate::{ItemGuard, Pool};
+
+/// A pooled item which returns to the pool when dropped.
+pub type Reusable<'a, T> = ItemGuard<'a, T>;
+
+/// `object-pool` style methods for `Pool`.
+pub trait PoolCompat<T> {
+    /// Get an item from the pool, creating one with `fallback` if it's empty.
+    fn pull<F: Fn() -> T>(&self, fallback: F) -> Reusable<'_, T>;
+
+    /// Get an item from the pool if there is one, without creating a new one.
+    fn try_pull(&self) -> Option<Reusable<'_, T>>;
+
+    /// Store an item in the pool.
+    fn attach(&self, item: T);
+}
+
+impl<T> PoolCompat<T> for Pool<T> {
+    #[track_caller]
+    fn pull<F: Fn() -> T>(&self, fallback: F) -> Reusable<'_, T> {
+        self.try_get_with(fallback).expect("pool is empty")
+    }
+
+    #[track_caller]
+    fn try_pull(&self) -> Option<Reusable<'_, T>> {
+        let item = self.internal.pop()?;
+        Some(ItemGuard::new(self, item, true))
+    }
+
+    fn attach(&self, item: T) {
+        self.internal.store(item);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{ItemGuard, Pool};
+use std::ops::{Deref, DerefMut};
+
+/// An item which keeps track of how many times it has been reused.
+///
+/// Created by `Pool::counted`.
+#[derive(Debug)]
+pub struct Counted<T> {
+    item: T,
+    reuses: u64,
+}
+
+impl<T> Counted<T> {
+    /// Returns how many times this item has been returned to the pool.
+    pub fn reuses(&self) -> u64 {
+        self.reuses
+    }
+
+    /// Unwrap the inner item.
+    pub fn into_inner(self) -> T {
+        self.item
+    }
+}
+
+impl<T> Deref for Counted<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.item
+    }
+}
+
+impl<T> DerefMut for Counted<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.item
+    }
+}
+
+impl<T> Pool<Counted<T>> {
+    /// Create a new Pool which counts how many times each item is reused.
+    pub fn counted<C>(create: C) -> Pool<Counted<T>>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+    {
+        Pool::counted_with_clear(create, |_| {})
+    }
+
+    /// Create a new Pool which counts how many times each item is reused, from
+    /// an initializer function and a clear function.
+    pub fn counted_with_clear<C, D>(create: C, clear: D) -> Pool<Counted<T>>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+        D: Fn(&mut T) + Send + Sync + 'static,
+    {
+        Pool::with_clear(
+            move || Counted {
+                item: create(),
+                reuses: 0,
+            },
+            move |counted| {
+                clear(&mut counted.item);
+                counted.reuses += 1;
+            },
+        )
+    }
+
+    /// Get an item from the pool along with the number of times it has been
+    /// reused. Freshly created items have been reused 0 times.
+    #[track_caller]
+    pub fn get_counted<'a>(&'a self) -> (ItemGuard<'a, Counted<T>>, u64) {
+        let item = self.get();
+        let reuses = item.reuses();
+        (item, reuses)
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{ItemGuard, Pool};
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+/// A group of pools, typically one per worker, which steal from each other.
+///
+/// A worker whose own pool is empty takes a stored item from one of its
+/// siblings before creating a new one. With `get_stealing`, stolen items
+/// belong to the stealing worker's pool from then on. With `borrow_stealing`,
+/// they're returned to the pool they were taken from.
+pub struct PoolGroup<T> {
+    pools: Vec<Pool<T>>,
+}
+
+impl<T> Debug for PoolGroup<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoolGroup")
+            .field("pools", &self.pools)
+            .finish()
+    }
+}
+
+impl<T> PoolGroup<T> {
+    /// Create a new PoolGroup of `count` pools sharing an initializer
+    /// function.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is 0.
+    pub fn new<C>(create: C, count: usize) -> PoolGroup<T>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+    {
+        assert!(count > 0, "a PoolGroup needs at least one pool");
+        let create = Arc::new(create);
+        PoolGroup {
+            pools: (0..count)
+                .map(|_| {
+                    let create = create.clone();
+                    Pool::new(move || create())
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the number of pools in the group.
+    pub fn len(&self) -> usize {
+        self.pools.len()
+    }
+
+    /// Always returns `false`, since a group has at least one pool.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Returns the pool at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn pool(&self, index: usize) -> &Pool<T> {
+        &self.pools[index]
+    }
+
+    /// Get an item from the pool at `my_index`, stealing one from the other
+    /// pools in turn if it's empty, and creating one if they're all empty.
+    ///
+    /// The item is returned to the pool at `my_index` when the guard is
+    /// dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `my_index` is out of bounds.
+    #[track_caller]
+    pub fn get_stealing<'a>(&'a self, my_index: usize) -> ItemGuard<'a, T> {
+        let local = &self.pools[my_index];
+        match self.steal(my_index) {
+            Some((_, item)) => ItemGuard::new(local, item, true),
+            None => local.get(),
+        }
+    }
+
+    /// Get an item like `get_stealing`, except that the item is returned to
+    /// the pool it was taken from when the guard is dropped.
+    ///
+    /// This keeps the pools balanced when some workers check out more items
+    /// than others.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `my_index` is out of bounds.
+    #[track_caller]
+    pub fn borrow_stealing<'a>(&'a self, my_index: usize) -> ItemGuard<'a, T> {
+        match self.steal(my_index) {
+            Some((origin, item)) => ItemGuard::new(&self.pools[origin], item, true),
+            None => self.pools[my_index].get(),
+        }
+    }
+
+    /// Take a stored item from the pool at `my_index`, or else from the other
+    /// pools in turn, along with the index of the pool it came from.
+    fn steal(&self, my_index: usize) -> Option<(usize, T)> {
+        let count = self.pools.len();
+        assert!(my_index < count, "pool index {} is out of bounds", my_index);
+        (0..count).find_map(|offset| {
+            let index = (my_index + offset) % count;
+            self.pools[index].internal.pop().map(|item| (index, item))
+        })
+    }
+}
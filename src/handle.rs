@@ -0,0 +1,43 @@
+use crate::Pool;
+use std::fmt::{self, Debug};
+
+/// A handle for returning items to a pool without holding a guard.
+///
+/// This is for types which embed a pooled item directly and want to return it
+/// in their own `Drop` impl. Created by `Pool::handle`.
+pub struct PoolHandle<T> {
+    pool: Pool<T>,
+}
+
+impl<T> Debug for PoolHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoolHandle")
+            .field("pool", &self.pool)
+            .finish()
+    }
+}
+
+impl<T> Clone for PoolHandle<T> {
+    fn clone(&self) -> Self {
+        PoolHandle {
+            pool: self.pool.clone(),
+        }
+    }
+}
+
+impl<T> PoolHandle<T> {
+    /// Clear `item` and store it in the pool, exactly as if a guard holding it
+    /// had been dropped.
+    ///
+    /// The item doesn't need to have come from the pool.
+    pub fn return_item(&self, item: T) {
+        self.pool.push(item);
+    }
+}
+
+impl<T> Pool<T> {
+    /// Create a handle for returning items to this pool.
+    pub fn handle(&self) -> PoolHandle<T> {
+        PoolHandle { pool: self.clone() }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{ItemGuard, OnDrop, Pool};
+use std::fmt::{self, Debug};
+use std::ops::Deref;
+
+/// RAII structure giving read-only access to a pooled item.
+///
+/// The item can't be modified through the guard, but it's still cleared as
+/// usual when the guard is dropped, since types with interior mutability can
+/// change behind a shared reference. See `Pool::get_ref_uncleared` to skip
+/// that.
+///
+/// ```compile_fail
+/// let pool = remem::Pool::new(|| vec![0u8; 4]);
+/// let mut item = pool.get_ref();
+/// *item = vec![1];
+/// ```
+pub struct ImmutableGuard<'a, T> {
+    guard: ItemGuard<'a, T>,
+}
+
+impl<T: Debug> Debug for ImmutableGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImmutableGuard")
+            .field("item", &*self.guard)
+            .finish()
+    }
+}
+
+impl<'a, T> Deref for ImmutableGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<T> Pool<T> {
+    /// Get read-only access to an item from the pool.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `get`.
+    #[track_caller]
+    pub fn get_ref<'a>(&'a self) -> ImmutableGuard<'a, T> {
+        ImmutableGuard { guard: self.get() }
+    }
+
+    /// Get read-only access to an item from the pool, which is returned to the
+    /// pool without running the clear function.
+    ///
+    /// This is only correct if the item can't change behind a shared
+    /// reference. Items with interior mutability, like a `Cell` or a `Mutex`,
+    /// can still be modified through the guard and would reach the next user
+    /// as they were left. The item also goes straight back to the queue, so
+    /// it isn't staged by `Builder::batched_return`, recorded by
+    /// `Builder::reserve_average`, or passed to `Builder::async_clear`.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `get`.
+    #[track_caller]
+    pub fn get_ref_uncleared<'a>(&'a self) -> ImmutableGuard<'a, T> {
+        let mut guard = self.get();
+        guard.on_drop = OnDrop::Store;
+        ImmutableGuard { guard }
+    }
+}
@@ -0,0 +1,129 @@
+//! `tower` middleware which hands a pooled item to every request.
+
+use crate::{AsyncItemGuard, Pool};
+use std::fmt::{self, Debug};
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// A request along with the pooled item checked out for it.
+#[derive(Debug)]
+pub struct PooledRequest<R, T> {
+    /// The original request.
+    pub request: R,
+    /// The item checked out for the request. It returns to the pool once it's
+    /// dropped, which may be after the response is sent if the inner service
+    /// holds on to it.
+    pub item: AsyncItemGuard<T>,
+}
+
+/// A `Layer` which wraps services in a `PoolService`.
+pub struct PoolLayer<T> {
+    pool: Pool<T>,
+}
+
+impl<T> Debug for PoolLayer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoolLayer")
+            .field("pool", &self.pool)
+            .finish()
+    }
+}
+
+impl<T> Clone for PoolLayer<T> {
+    fn clone(&self) -> Self {
+        PoolLayer {
+            pool: self.pool.clone(),
+        }
+    }
+}
+
+impl<T> PoolLayer<T> {
+    /// Create a new PoolLayer which checks items out of `pool`.
+    pub fn new(pool: Pool<T>) -> PoolLayer<T> {
+        PoolLayer { pool }
+    }
+}
+
+impl<S, T> Layer<S> for PoolLayer<T> {
+    type Service = PoolService<S, T>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        PoolService::new(inner, self.pool.clone())
+    }
+}
+
+/// A `Service` which checks an item out of a pool for every request, and
+/// passes both on to the inner service as a `PooledRequest`.
+///
+/// The item is checked out in `poll_ready`. For pools which use
+/// `GrowthPolicy::FailOnEmpty` or `GrowthPolicy::Block`, the service isn't
+/// ready while the pool is empty, and becomes ready again once an item is
+/// returned. This applies backpressure when the pool is bounded. Pools which
+/// always allocate are always ready.
+pub struct PoolService<S, T> {
+    inner: S,
+    pool: Pool<T>,
+    reserved: Option<AsyncItemGuard<T>>,
+}
+
+impl<S: Debug, T> Debug for PoolService<S, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoolService")
+            .field("inner", &self.inner)
+            .field("pool", &self.pool)
+            .field("reserved", &self.reserved.is_some())
+            .finish()
+    }
+}
+
+impl<S: Clone, T> Clone for PoolService<S, T> {
+    /// The clone doesn't share the item reserved by this service, if any.
+    fn clone(&self) -> Self {
+        PoolService::new(self.inner.clone(), self.pool.clone())
+    }
+}
+
+impl<S, T> PoolService<S, T> {
+    /// Create a new PoolService wrapping `inner`, which checks items out of
+    /// `pool`.
+    pub fn new(inner: S, pool: Pool<T>) -> PoolService<S, T> {
+        PoolService {
+            inner,
+            pool,
+            reserved: None,
+        }
+    }
+
+    /// Returns the pool items are checked out of.
+    pub fn pool(&self) -> &Pool<T> {
+        &self.pool
+    }
+}
+
+impl<S, T, R> Service<R> for PoolService<S, T>
+where
+    S: Service<PooledRequest<R, T>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.reserved.is_none() {
+            let (item, hit) = match self.pool.internal.poll_acquire(cx) {
+                Poll::Ready(acquired) => acquired,
+                Poll::Pending => return Poll::Pending,
+            };
+            self.reserved = Some(AsyncItemGuard::new(&self.pool, item, hit));
+        }
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let item = self
+            .reserved
+            .take()
+            .expect("PoolService::call was called before poll_ready");
+        self.inner.call(PooledRequest { request, item })
+    }
+}
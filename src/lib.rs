@@ -48,31 +48,481 @@
 //! ```
 
 #![forbid(rust_2018_idioms)]
-#![deny(missing_debug_implementations, nonstandard_style)]
+#![deny(missing_debug_implementations, nonstandard_style, unsafe_code)]
 #![warn(missing_docs, missing_doc_code_examples, unreachable_pub)]
 #![cfg_attr(test, deny(warnings))]
 
+use adaptive::Adaptive;
+use arc_swap::ArcSwap;
+use atomic::{AtomicU64, AtomicUsize, Ordering};
+#[cfg(all(feature = "unstable", not(miri)))]
 use crossbeam_queue::SegQueue;
+use std::convert::TryFrom;
 use std::fmt::{self, Debug};
+use std::hint;
+use std::io;
+use std::iter;
+use std::mem::{self, ManuallyDrop};
 use std::ops::{Deref, DerefMut};
-use std::sync::Arc;
+use std::sync::{Arc, Condvar, Mutex};
+#[cfg(any(feature = "stream", feature = "tower"))]
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+pub mod compat;
+#[cfg(feature = "registry")]
+pub mod registry;
+pub mod unsync;
+
+mod adaptive;
+mod any;
+mod arc;
+#[cfg(feature = "bumpalo")]
+mod arena;
+#[cfg(feature = "tokio")]
+mod async_clear;
+#[cfg(feature = "tokio")]
+mod async_guard;
+mod atomic;
+mod average;
+#[cfg(feature = "tokio")]
+mod awaitable;
+mod batch;
+mod budget;
+mod builder;
+mod bytes;
+mod counted;
+mod group;
+mod handle;
+mod immutable;
+#[cfg(feature = "tower")]
+mod layer;
+mod mapped;
+mod meta;
+#[cfg(feature = "numa")]
+mod numa;
+mod origin;
+mod pinned;
+mod ring;
+mod routed;
+mod seed;
+mod shared;
+mod size_class;
+mod stats;
+mod store;
+#[cfg(feature = "stream")]
+mod stream;
+mod tiered;
+mod try_pool;
+mod write_once;
+
+pub use adaptive::AdaptivePolicy;
+pub use any::PooledAny;
+pub use arc::PooledInner;
+#[cfg(feature = "bumpalo")]
+pub use arena::ArenaPool;
+#[cfg(feature = "tokio")]
+pub use async_clear::BoxFuture;
+#[cfg(feature = "tokio")]
+pub use async_guard::AsyncItemGuard;
+#[cfg(feature = "tokio")]
+pub use awaitable::{AwaitableGuard, Completion};
+pub use builder::{Builder, GrowthPolicy};
+pub use counted::Counted;
+pub use group::PoolGroup;
+pub use handle::PoolHandle;
+pub use immutable::ImmutableGuard;
+#[cfg(feature = "tower")]
+pub use layer::{PoolLayer, PoolService, PooledRequest};
+pub use mapped::MappedGuard;
+pub use meta::{MetaPool, WithMeta};
+#[cfg(feature = "numa")]
+pub use numa::NumaPool;
+pub use pinned::PinnedGuard;
+pub use ring::PoolRing;
+pub use routed::RoutedGuard;
+pub use seed::SeedError;
+pub use shared::try_lock_clear;
+pub use size_class::SizeClassPool;
+pub use stats::StatsSink;
+#[cfg(feature = "stream")]
+pub use stream::PoolStream;
+pub use tiered::TieredPool;
+pub use try_pool::TryPool;
+pub use write_once::WriteOnceGuard;
+
+/// A function which grows an item to fit at least the given size.
+type Grow<T> = Box<dyn Fn(&mut T, usize) + Send + Sync>;
+
+/// A function which runs over an item, such as a clear function.
+type Visit<T> = Box<dyn Fn(&mut T) + Send + Sync>;
+
+/// A clear function which can be shared between pools.
+type Clear<T> = Arc<dyn Fn(&mut T) + Send + Sync>;
 
 struct Internal<T> {
-    queue: SegQueue<T>,
-    create: Box<dyn Fn() -> T + Send + Sync>,
-    clear: Box<dyn Fn(&mut T) + Send + Sync>,
+    queue: store::Store<T>,
+    // These two are shared so `Pool::new_like` can reuse them.
+    create: Arc<dyn Fn() -> T + Send + Sync>,
+    // Swapped atomically, so returns don't take a lock to read it.
+    clear: ArcSwap<Clear<T>>,
+    clear_owned: Option<Box<dyn Fn(T) -> T + Send + Sync>>,
+    create_sized: Option<Box<dyn Fn(usize) -> T + Send + Sync>>,
+    grow: Option<Grow<T>>,
+    #[cfg(feature = "tokio")]
+    async_clear: Option<async_clear::AsyncClear<T>>,
+    growth_policy: GrowthPolicy,
+    capacity: AtomicUsize,
+    spin_tries: usize,
+    /// How many items are still to be created by the first checkout.
+    warm: AtomicUsize,
+    warmup_wait: Option<Duration>,
+    waiters: AtomicUsize,
+    lock: Mutex<()>,
+    returned: Condvar,
+    #[cfg(any(feature = "stream", feature = "tower"))]
+    wakers: Mutex<Vec<Waker>>,
+    in_use: AtomicUsize,
+    next_tag: AtomicU64,
+    /// Counts of the sizes requested from `get_with_capacity` on misses,
+    /// bucketed by the next power of two.
+    miss_sizes: Mutex<Vec<u64>>,
+    adaptive: Option<Adaptive>,
+    on_get: Option<Box<dyn Fn(bool) + Send + Sync>>,
+    on_pool_drop: Option<Visit<T>>,
+    spill: Option<Pool<T>>,
+    dedup: Option<fn(&T, &T) -> bool>,
+    identity: Option<fn(&T) -> usize>,
+    average: Option<average::Average<T>>,
+    stage: Option<batch::Stage<T>>,
+    stats: Option<Arc<dyn StatsSink>>,
+    budget: Option<budget::Budget>,
+    origins: origin::Origins,
+    max_hold: Option<origin::MaxHold>,
 }
 
 impl<T> Internal<T> {
-    fn new<C, D>(create: C, clear: D) -> Self
+    /// Put an item in the queue, waking up a blocked `get` if there is one.
+    ///
+    /// The item is dropped instead if the queue is full.
+    fn store(&self, item: T) {
+        if self.enqueue(item) {
+            self.notify(false);
+        }
+    }
+
+    /// Put an item in the queue without waking anyone up. Returns `false` if
+    /// the queue is full, in which case the item is passed on to the spill
+    /// pool if there is one, and dropped otherwise.
+    fn enqueue(&self, item: T) -> bool {
+        self.enqueue_with(item, true)
+    }
+
+    /// The same as `enqueue`, but only applies the adaptive limit if
+    /// `adaptive` is set. Items created to fill the pool ignore it, since the
+    /// caller asked for them explicitly.
+    fn enqueue_with(&self, item: T, adaptive: bool) -> bool {
+        let mut capacity = self.capacity.load(Ordering::Relaxed);
+        if let (true, Some(adaptive)) = (adaptive, &self.adaptive) {
+            capacity = capacity.min(adaptive.limit());
+        }
+        if self.queue.len() >= capacity {
+            if let Some(spill) = &self.spill {
+                spill.internal.store(item);
+            }
+            return false;
+        }
+        if let Some(eq) = self.dedup {
+            // Hold the lock so concurrent returns of equal items can't both get
+            // in.
+            let _lock = self.lock.lock().unwrap();
+            let stored = self.drain();
+            let duplicate = stored.iter().any(|stored| eq(stored, &item));
+            stored
+                .into_iter()
+                .for_each(|stored| self.queue.push(stored));
+            if duplicate {
+                return false;
+            }
+        }
+        self.queue.push(item);
+        true
+    }
+
+    /// Take an item out of the queue, or create one with `create`, according
+    /// to the growth policy. Also returns whether the item was reused.
+    fn acquire<F>(&self, create: F) -> Option<(T, bool)>
     where
-        C: Fn() -> T + Send + Sync + 'static,
-        D: Fn(&mut T) -> () + Send + Sync + 'static,
+        F: FnOnce() -> T,
     {
-        Internal {
-            queue: SegQueue::new(),
-            create: Box::new(create),
-            clear: Box::new(clear),
+        let (mut item, hit) = match self.pop() {
+            Some(item) => (item, true),
+            None => match self.growth_policy {
+                GrowthPolicy::AlwaysAllocate => match self.wait_for_return() {
+                    Some(item) => (item, true),
+                    None => (create(), false),
+                },
+                GrowthPolicy::FailOnEmpty => return None,
+                GrowthPolicy::Block => (self.wait(None).unwrap(), true),
+            },
+        };
+        if let (true, Some(average)) = (hit, &self.average) {
+            average.apply(&mut item);
+        }
+        Some((item, hit))
+    }
+
+    /// Wait up to `Builder::warmup_wait` for an item to be returned to an empty
+    /// pool, if any items are checked out which could be returned.
+    fn wait_for_return(&self) -> Option<T> {
+        let timeout = self.warmup_wait?;
+        if self.in_use.load(Ordering::Relaxed) == 0 {
+            return None;
+        }
+        self.wait(Some(timeout))
+    }
+
+    /// Record that an item was checked out, which was either reused from the
+    /// queue (a hit) or newly created.
+    #[track_caller]
+    fn check_out(&self, item: &T, hit: bool) -> origin::Origin {
+        // This panics if the item was issued twice, so do it before the
+        // checkout is counted anywhere.
+        let origin = self.origins.check_out(
+            self.identity.map(|identity| identity(item)),
+            self.max_hold.is_some(),
+        );
+        let in_use = self.in_use.fetch_add(1, Ordering::Relaxed) + 1;
+        self.record_check_out(hit, in_use);
+        origin
+    }
+
+    /// Count a checkout of an item which didn't come from the pool in
+    /// `in_use`, without recording it anywhere else.
+    #[track_caller]
+    fn check_out_untracked(&self) -> origin::Origin {
+        let origin = self.origins.check_out(None, self.max_hold.is_some());
+        self.in_use.fetch_add(1, Ordering::Relaxed);
+        origin
+    }
+
+    /// Count a checkout in `in_use` ahead of time, unless `max_in_use` items
+    /// are already checked out.
+    fn reserve(&self, max_in_use: usize) -> Option<Reservation<'_, T>> {
+        self.in_use
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                (n < max_in_use).then_some(n + 1)
+            })
+            .ok()?;
+        Some(Reservation(self))
+    }
+
+    /// The same as `check_out`, for a checkout already counted by `reserve`.
+    #[track_caller]
+    fn check_out_reserved(
+        &self,
+        reservation: Reservation<'_, T>,
+        item: &T,
+        hit: bool,
+    ) -> origin::Origin {
+        // If this panics the reservation is given back.
+        let origin = self.origins.check_out(
+            self.identity.map(|identity| identity(item)),
+            self.max_hold.is_some(),
+        );
+        mem::forget(reservation);
+        self.record_check_out(hit, self.in_use.load(Ordering::Relaxed));
+        origin
+    }
+
+    /// Record a checkout which brought the number of items in use to
+    /// `in_use`.
+    fn record_check_out(&self, hit: bool, in_use: usize) {
+        if let Some(on_get) = &self.on_get {
+            on_get(hit);
+        }
+        if let Some(stats) = &self.stats {
+            stats.record_get(hit);
+        }
+        if let (false, Some(budget)) = (hit, &self.budget) {
+            budget.record();
+        }
+        if let Some(adaptive) = &self.adaptive {
+            adaptive.record(in_use);
+        }
+        if self.warm.load(Ordering::Relaxed) != 0 {
+            self.warm_up();
+        }
+    }
+
+    /// Store the items requested with `Builder::warm_on_first_get`. Only the
+    /// first caller creates them.
+    #[cold]
+    fn warm_up(&self) {
+        for _ in 0..self.warm.swap(0, Ordering::Relaxed) {
+            if !self.enqueue_with((self.create)(), false) {
+                break;
+            }
+        }
+        self.notify(true);
+    }
+
+    /// Record that an item checked out with `check_out` is no longer in use.
+    fn check_in(&self, origin: &origin::Origin) {
+        self.origins.check_in(origin);
+        if let Some(max_hold) = &self.max_hold {
+            max_hold.check(origin);
+        }
+        // Pairs with the load in `drain_with_wait`, so the item stored before
+        // this is visible once the count drops.
+        let in_use = self.in_use.fetch_sub(1, Ordering::Release) - 1;
+        if let Some(adaptive) = &self.adaptive {
+            adaptive.record(in_use);
+        }
+        if in_use == 0 {
+            // Wake up `drain_with_wait`, which waits for the last guard.
+            self.notify(true);
+        }
+    }
+
+    /// Run the clear functions on an item which is about to be stored.
+    fn reset(&self, mut item: T) -> T {
+        if let Some(average) = &self.average {
+            average.record(&item);
+        }
+        (self.clear.load())(&mut item);
+        match &self.clear_owned {
+            Some(clear_owned) => clear_owned(item),
+            None => item,
+        }
+    }
+
+    /// Take every item out of the queue.
+    fn drain(&self) -> Vec<T> {
+        let mut items = Vec::with_capacity(self.queue.len());
+        while let Ok(item) = self.queue.pop() {
+            items.push(item);
+        }
+        items
+    }
+
+    /// Wake up one or all threads waiting for an item, if there are any.
+    fn notify(&self, all: bool) {
+        // Pairs with the fence in `wait`, so either the waiter sees the item we
+        // just pushed or we see the waiter.
+        atomic::fence(Ordering::SeqCst);
+        if self.waiters.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+        let _lock = self.lock.lock().unwrap();
+        #[cfg(any(feature = "stream", feature = "tower"))]
+        {
+            let wakers = mem::take(&mut *self.wakers.lock().unwrap());
+            self.waiters.fetch_sub(wakers.len(), Ordering::Relaxed);
+            wakers.into_iter().for_each(Waker::wake);
+        }
+        if all {
+            self.returned.notify_all();
+        } else {
+            self.returned.notify_one();
+        }
+    }
+
+    /// Take an item out of the queue, retrying up to `spin_tries` times if it's
+    /// empty.
+    fn pop(&self) -> Option<T> {
+        let mut tries = self.spin_tries;
+        loop {
+            match self.queue.pop() {
+                Ok(item) => return Some(item),
+                Err(_) if tries == 0 => return None,
+                Err(_) => {
+                    tries -= 1;
+                    hint::spin_loop();
+                }
+            }
+        }
+    }
+
+    /// Take an item out of the queue, or arrange for the task to be woken up
+    /// once one is returned.
+    #[cfg(any(feature = "stream", feature = "tower"))]
+    fn poll_pop(&self, cx: &mut Context<'_>) -> Poll<T> {
+        if let Some(item) = self.pop() {
+            return Poll::Ready(item);
+        }
+        {
+            let mut wakers = self.wakers.lock().unwrap();
+            if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                wakers.push(cx.waker().clone());
+                self.waiters.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        // Pairs with the fence in `notify`, like in `wait`.
+        atomic::fence(Ordering::SeqCst);
+        match self.queue.pop() {
+            Ok(item) => Poll::Ready(item),
+            Err(_) => Poll::Pending,
+        }
+    }
+
+    /// Take an item out of the queue, or create one, according to the growth
+    /// policy. Instead of blocking, pools which don't always allocate arrange
+    /// for the task to be woken up once an item is returned. Also returns
+    /// whether the item was reused.
+    #[cfg(any(feature = "stream", feature = "tower"))]
+    fn poll_acquire(&self, cx: &mut Context<'_>) -> Poll<(T, bool)> {
+        let mut item = match (self.growth_policy, self.pop()) {
+            (_, Some(item)) => item,
+            (GrowthPolicy::AlwaysAllocate, None) => return Poll::Ready(((self.create)(), false)),
+            (GrowthPolicy::FailOnEmpty | GrowthPolicy::Block, None) => match self.poll_pop(cx) {
+                Poll::Ready(item) => item,
+                Poll::Pending => return Poll::Pending,
+            },
+        };
+        if let Some(average) = &self.average {
+            average.apply(&mut item);
+        }
+        Poll::Ready((item, true))
+    }
+
+    /// Wait until an item is available in the queue, or until `timeout` has
+    /// passed.
+    fn wait(&self, timeout: Option<Duration>) -> Option<T> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut lock = self.lock.lock().unwrap();
+        self.waiters.fetch_add(1, Ordering::Relaxed);
+        atomic::fence(Ordering::SeqCst);
+        let item = loop {
+            if let Ok(item) = self.queue.pop() {
+                break Some(item);
+            }
+            match deadline {
+                None => lock = self.returned.wait(lock).unwrap(),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        break None;
+                    }
+                    lock = self.returned.wait_timeout(lock, deadline - now).unwrap().0;
+                }
+            }
+        };
+        self.waiters.fetch_sub(1, Ordering::Relaxed);
+        item
+    }
+}
+
+/// A checkout counted in `Internal::in_use` before its item was acquired,
+/// which is given back if it's dropped instead of being checked out.
+struct Reservation<'a, T>(&'a Internal<T>);
+
+impl<T> Drop for Reservation<'_, T> {
+    fn drop(&mut self) {
+        let pool = self.0;
+        if pool.in_use.fetch_sub(1, Ordering::Release) == 1 {
+            pool.notify(true);
         }
     }
 }
@@ -82,12 +532,25 @@ pub struct Pool<T> {
     internal: Arc<Internal<T>>,
 }
 
+impl<T> Drop for Internal<T> {
+    fn drop(&mut self) {
+        if let Some(on_pool_drop) = &self.on_pool_drop {
+            while let Ok(mut item) = self.queue.pop() {
+                on_pool_drop(&mut item);
+            }
+        }
+    }
+}
+
 impl<T> Debug for Pool<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Pool")
             .field("queue", &format!("[T; {}]", self.internal.queue.len()))
             .field("create", &"Box<dyn Fn() -> T + Send + Sync>")
             .field("clear", &"Box<dyn Fn(&mut T) + Send + Sync>")
+            .field("growth_policy", &self.internal.growth_policy)
+            .field("capacity", &self.capacity())
+            .field("spin_tries", &self.internal.spin_tries)
             .finish()
     }
 }
@@ -98,9 +561,7 @@ impl<T> Pool<T> {
     where
         C: Fn() -> T + Send + Sync + 'static,
     {
-        Pool {
-            internal: Arc::new(Internal::new(create, |_| {})),
-        }
+        Pool::builder(create).build()
     }
 
     /// Create a new Pool from an initializer function and a clear function.
@@ -115,27 +576,607 @@ impl<T> Pool<T> {
     pub fn with_clear<C, D>(create: C, clear: D) -> Pool<T>
     where
         C: Fn() -> T + Send + Sync + 'static,
-        D: Fn(&mut T) -> () + Send + Sync + 'static,
+        D: Fn(&mut T) + Send + Sync + 'static,
     {
-        Pool {
-            internal: Arc::new(Internal::new(create, clear)),
-        }
+        Pool::builder(create).clear(clear).build()
+    }
+
+    /// Create a new Pool from an initializer function which needs mutable
+    /// access to its state, like a counter used to name items.
+    ///
+    /// The function is kept behind a `Mutex` which is locked every time an item
+    /// is created, so misses on different threads wait for each other. Hits
+    /// don't touch the lock. Prefer `new` with atomics or other shared state
+    /// when misses are frequent and contended.
+    pub fn with_fnmut_create<C>(create: C) -> Pool<T>
+    where
+        C: FnMut() -> T + Send + 'static,
+    {
+        let create = Mutex::new(create);
+        Pool::new(move || (create.lock().unwrap())())
+    }
+
+    /// Create a new Pool which hands out the largest stored item first, as
+    /// measured by `size_of`.
+    ///
+    /// Reusing the largest buffer available keeps buffers from having to grow
+    /// again later. The items are kept in a binary heap behind a lock rather
+    /// than in the lock-free queue, so checkouts and returns are slower and
+    /// contend with each other.
+    pub fn with_size_priority<C, S>(create: C, size_of: S) -> Pool<T>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+        S: Fn(&T) -> usize + Send + Sync + 'static,
+    {
+        Pool::builder(create).size_priority(size_of).build()
+    }
+
+    /// Create a new Pool which creates items by cloning `template`.
+    pub fn from_template(template: T) -> Pool<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        Pool::new(move || template.clone())
+    }
+
+    /// Create a new, independent Pool with the same create and clear functions
+    /// as this one.
+    ///
+    /// Unlike `clone`, which returns another handle to the same pool, the new
+    /// pool starts out empty and doesn't share any items with this one. Its
+    /// growth policy, capacity and spin tries are copied too, as are the
+    /// `Builder` settings which wrap the create and clear functions, like
+    /// `prefault` and `poison_on_return`. A clear function set with
+    /// `replace_clear` is copied as it is now.
+    ///
+    /// Nothing else is copied. The new pool doesn't have any of
+    /// `clear_owned`, `create_sized`, `grow`, `async_clear`,
+    /// `warm_on_first_get`, `warmup_wait`, `adaptive`, `on_get`,
+    /// `on_pool_drop`, `dedup`, `collect_stats_into`, `alloc_budget`,
+    /// `max_hold`, `size_priority`, `check_double_issue`, `batched_return`,
+    /// `reserve_average` or `track_pooled_bytes`, and items it can't store
+    /// don't spill over to a `TieredPool`'s second level.
+    pub fn new_like(&self) -> Pool<T> {
+        let pool = &self.internal;
+        Builder::with_shared(pool.create.clone(), Clear::clone(&pool.clear.load()))
+            .growth_policy(pool.growth_policy)
+            .capacity(self.capacity())
+            .spin_tries(pool.spin_tries)
+            .build()
+    }
+
+    /// Create a `Builder` to configure a new Pool from an initializer function.
+    pub fn builder<C>(create: C) -> Builder<T>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+    {
+        Builder::new(create)
     }
 
     /// Get an item from the pool.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pool is empty and its growth policy is
+    /// `GrowthPolicy::FailOnEmpty`. Use `try_get` to handle that case.
+    #[track_caller]
     pub fn get<'a>(&'a self) -> ItemGuard<'a, T> {
+        self.try_get().expect("pool is empty")
+    }
+
+    /// Get an item from the pool, run `f` on it, then return it to the pool.
+    ///
+    /// This is the same as `pool.get().with(f)`, for scratch items which don't
+    /// need to outlive a single call.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `get`.
+    #[track_caller]
+    pub fn using<R, F: FnOnce(&mut T) -> R>(&self, f: F) -> R {
+        self.get().with(f)
+    }
+
+    /// Get an item from the pool along with whether it was reused or newly
+    /// created.
+    ///
+    /// This lets callers skip initializing fresh items, which are already in
+    /// the state the create function left them in.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `get`.
+    #[track_caller]
+    pub fn get_checked<'a>(&'a self) -> (ItemGuard<'a, T>, Freshness) {
+        let mut freshness = Freshness::Reused;
+        let guard = self
+            .try_get_with(|| {
+                freshness = Freshness::Fresh;
+                (*self.internal.create)()
+            })
+            .expect("pool is empty");
+        (guard, freshness)
+    }
+
+    /// Get an item from the pool along with an id which is unique to this
+    /// checkout.
+    ///
+    /// Ids increase with every call, per pool, and aren't tied to the
+    /// underlying item, which gets a new id each time it's checked out. This is
+    /// useful for tracing an item's journey through a pipeline.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `get`.
+    #[track_caller]
+    pub fn get_tagged<'a>(&'a self) -> (ItemGuard<'a, T>, u64) {
+        let item = self.get();
+        let tag = self.internal.next_tag.fetch_add(1, Ordering::Relaxed);
+        (item, tag)
+    }
+
+    /// Get `N` items from the pool at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `get`.
+    #[track_caller]
+    pub fn get_array<'a, const N: usize>(&'a self) -> [ItemGuard<'a, T>; N] {
+        // A loop rather than `array::from_fn`, since a closure would be
+        // reported as the checkout location instead of the caller.
+        let mut guards = Vec::with_capacity(N);
+        for _ in 0..N {
+            guards.push(self.get());
+        }
+        match <[ItemGuard<'a, T>; N]>::try_from(guards) {
+            Ok(guards) => guards,
+            Err(_) => unreachable!(),
+        }
+    }
+
+    /// Returns an endless iterator of items from the pool.
+    ///
+    /// Each call to `next` is the same as calling `get`, so use adapters like
+    /// `take` to limit how many items are checked out.
+    pub fn guards<'a>(&'a self) -> impl Iterator<Item = ItemGuard<'a, T>> + 'a {
+        iter::repeat_with(move || self.get())
+    }
+
+    /// Get an item from the pool, respecting its growth policy.
+    ///
+    /// Returns `None` if the pool is empty and its growth policy is
+    /// `GrowthPolicy::FailOnEmpty`. With `GrowthPolicy::Block` this waits until
+    /// an item is returned to the pool.
+    #[track_caller]
+    pub fn try_get<'a>(&'a self) -> Option<ItemGuard<'a, T>> {
+        self.try_get_with(|| (*self.internal.create)())
+    }
+
+    /// Get an item from the pool unless `max_in_use` items are already checked
+    /// out.
+    ///
+    /// This caps how many items are in use at once, for flow control. Returns
+    /// `None` if the cap is reached, or under the same conditions as
+    /// `try_get`.
+    #[track_caller]
+    pub fn try_get_bounded<'a>(&'a self, max_in_use: usize) -> Option<ItemGuard<'a, T>> {
         let pool = &self.internal;
-        let item = pool.queue.pop();
-        ItemGuard {
-            item: Some(item.unwrap_or_else(|_| (*self.internal.create)())),
-            pool: self,
+        // Count the checkout first so concurrent calls can't exceed the cap.
+        let reservation = pool.reserve(max_in_use)?;
+        let (item, hit) = pool.acquire(|| (pool.create)())?;
+        let origin = pool.check_out_reserved(reservation, &item, hit);
+        Some(ItemGuard::with_origin(self, item, origin))
+    }
+
+    /// Get an item from the pool, respecting its growth policy, and using
+    /// `create` if a new item needs to be created.
+    #[track_caller]
+    fn try_get_with<'a, F>(&'a self, create: F) -> Option<ItemGuard<'a, T>>
+    where
+        F: FnOnce() -> T,
+    {
+        let (item, hit) = self.internal.acquire(create)?;
+        Some(ItemGuard::new(self, item, hit))
+    }
+
+    /// Get an item from the pool, creating it with `alloc` instead of the
+    /// pool's initializer function if the pool is empty.
+    ///
+    /// This lets a single checkout use a different allocation source, like a
+    /// huge page allocator for a large request. The item is returned to the
+    /// pool like any other once the guard is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `get`.
+    #[track_caller]
+    pub fn get_with_alloc<'a, F>(&'a self, alloc: F) -> ItemGuard<'a, T>
+    where
+        F: FnOnce() -> T,
+    {
+        self.try_get_with(alloc).expect("pool is empty")
+    }
+
+    /// Get an item from the pool which fits at least `hint` elements.
+    ///
+    /// New items are created with the function set by `Builder::create_sized`,
+    /// and reused items are grown with the function set by `Builder::grow`.
+    /// Without those this is the same as `get`.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `get`.
+    #[track_caller]
+    pub fn get_sized<'a>(&'a self, hint: usize) -> ItemGuard<'a, T> {
+        let pool = &self.internal;
+        let mut created = false;
+        let mut guard = self
+            .try_get_with(|| {
+                created = true;
+                match &pool.create_sized {
+                    Some(create_sized) => create_sized(hint),
+                    None => (*pool.create)(),
+                }
+            })
+            .expect("pool is empty");
+        if let (false, Some(grow)) = (created, &pool.grow) {
+            grow(&mut guard, hint);
+        }
+        guard
+    }
+
+    /// Get an item from the pool, waiting up to `timeout` for one to be
+    /// returned if the pool is empty.
+    ///
+    /// If the pool is still empty once `timeout` has passed, a new item is
+    /// created regardless of the pool's growth policy. This trades a little
+    /// latency for more reuse.
+    #[track_caller]
+    pub fn try_get_for<'a>(&'a self, timeout: Duration) -> ItemGuard<'a, T> {
+        self.get_waiting(timeout).0
+    }
+
+    /// The same as `try_get_for`, but also returns `true` if the item was
+    /// reused and `false` if it was newly created.
+    ///
+    /// This helps tune `timeout` to balance reuse against latency.
+    #[track_caller]
+    pub fn get_waiting<'a>(&'a self, timeout: Duration) -> (ItemGuard<'a, T>, bool) {
+        let pool = &self.internal;
+        match pool.pop().or_else(|| pool.wait(Some(timeout))) {
+            Some(item) => (ItemGuard::new(self, item, true), true),
+            None => (ItemGuard::new(self, (*pool.create)(), false), false),
+        }
+    }
+
+    /// Create `count` new items and store them in the pool.
+    ///
+    /// Stops early if the pool reaches its capacity.
+    pub fn prefill(&self, count: usize) {
+        for _ in 0..count {
+            if self.len() >= self.capacity() {
+                break;
+            }
+            let pool = &self.internal;
+            if pool.enqueue_with((*pool.create)(), false) {
+                pool.notify(false);
+            }
+        }
+    }
+
+    /// Returns the number of items stored in the pool.
+    pub fn len(&self) -> usize {
+        self.internal.queue.len()
+    }
+
+    /// Returns `true` if there are no items stored in the pool.
+    pub fn is_empty(&self) -> bool {
+        self.internal.queue.is_empty()
+    }
+
+    /// Returns the number of guards handed out by the pool which haven't been
+    /// dropped yet, including guards created from other handles to the pool.
+    pub fn in_use(&self) -> usize {
+        self.internal.in_use.load(Ordering::Relaxed)
+    }
+
+    /// Access the queue storing the pool's items directly.
+    ///
+    /// This is an escape hatch for operations the rest of the API doesn't
+    /// cover, and can break the pool's guarantees: items pushed here skip the
+    /// clear function and the pool's capacity, and don't wake up calls to
+    /// `get` blocked by `GrowthPolicy::Block`. The queue's type is tied to the
+    /// version of `crossbeam-queue` used by this crate, so it may change in any
+    /// release.
+    ///
+    /// Not available under Miri, where the pool uses a locked queue instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pool was created with `with_size_priority`, since it
+    /// keeps its items in a heap instead.
+    #[cfg(all(feature = "unstable", not(miri)))]
+    pub fn raw_queue(&self) -> &SegQueue<T> {
+        self.internal
+            .queue
+            .queue()
+            .expect("pools with a size priority don't use a queue")
+    }
+
+    /// Returns a clone of the next item `get` would return, without removing it
+    /// from the pool.
+    ///
+    /// The item is taken out of the pool and pushed back to the end of the
+    /// queue, so this moves it behind every other stored item.
+    pub fn peek_one(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        let pool = &self.internal;
+        let item = pool.queue.pop().ok()?;
+        let clone = item.clone();
+        // The item was already stored, so skip the capacity and dedup checks
+        // which could drop it.
+        pool.queue.push(item);
+        pool.notify(false);
+        Some(clone)
+    }
+
+    /// Returns the maximum number of items the pool stores.
+    pub fn capacity(&self) -> usize {
+        self.internal.capacity.load(Ordering::Relaxed)
+    }
+
+    /// Change the maximum number of items the pool stores.
+    ///
+    /// Lowering the capacity doesn't evict any items, but items returned to a
+    /// full pool are dropped. Excess items drain naturally as they're taken
+    /// out of the pool.
+    pub fn set_capacity(&self, capacity: usize) {
+        self.internal.capacity.store(capacity, Ordering::Relaxed);
+    }
+
+    /// Move all items stored in `other` into this pool.
+    ///
+    /// Items which don't fit within this pool's capacity are dropped. Items are
+    /// moved as-is without running the clear function. Only this
+    /// pool's create and clear functions are used for them from then on.
+    pub fn merge(&self, other: &Pool<T>) {
+        if Arc::ptr_eq(&self.internal, &other.internal) {
+            return;
+        }
+        while let Ok(item) = other.internal.queue.pop() {
+            self.internal.store(item);
+        }
+    }
+
+    /// Panic if any guards handed out by this pool haven't been dropped.
+    ///
+    /// Guards which are leaked, for example with `mem::forget`, never return
+    /// their item and cause the pool to allocate new ones. Call this once all
+    /// guards are expected to be dropped, for example at the end of a test.
+    ///
+    /// This only counts guards; see `in_use`. With the `leak_detection`
+    /// feature the panic message also lists where each remaining guard was
+    /// checked out.
+    pub fn assert_no_leaks(&self) {
+        let in_use = self.in_use();
+        if in_use == 0 {
+            return;
+        }
+        let mut message = format!("{} guard(s) were not returned to the pool", in_use);
+        let live = self.internal.origins.live();
+        if !live.is_empty() {
+            message.push_str(", checked out at:");
+            for location in live {
+                message.push_str(&format!("\n    {}", location));
+            }
+        }
+        panic!("{}", message);
+    }
+
+    /// Replace every item stored in the pool with the result of `f`.
+    ///
+    /// The pool is drained before `f` runs, so calls to `get` made while this
+    /// is running find the pool empty. The clear function isn't run on the
+    /// transformed items.
+    pub fn transform_all<F>(&self, f: F)
+    where
+        F: Fn(T) -> T,
+    {
+        let pool = &self.internal;
+        let items = pool.drain();
+        for item in items {
+            pool.enqueue(f(item));
+        }
+        pool.notify(true);
+    }
+
+    /// Replace the function used to clear items before they're returned to the
+    /// pool.
+    ///
+    /// Items returned from now on use the new function. Items already stored
+    /// in the pool aren't affected.
+    pub fn replace_clear<D>(&self, clear: D)
+    where
+        D: Fn(&mut T) + Send + Sync + 'static,
+    {
+        let clear: Clear<T> = Arc::new(clear);
+        self.internal.clear.store(Arc::new(clear));
+    }
+
+    /// Remove and return every stored item, then wait up to `timeout` for
+    /// checked out items to be returned, and remove those too.
+    ///
+    /// This is meant for shutting down gracefully. It stops waiting early once
+    /// no guards are left. Returned items are cleared as usual before they're
+    /// collected. Items which are still staged by `Builder::batched_return`,
+    /// or still being cleared by `Builder::async_clear`, aren't waited for.
+    pub fn drain_with_wait(&self, timeout: Duration) -> Vec<T> {
+        let pool = &self.internal;
+        let deadline = Instant::now() + timeout;
+        let mut items = Vec::new();
+        let mut lock = pool.lock.lock().unwrap();
+        pool.waiters.fetch_add(1, Ordering::Relaxed);
+        atomic::fence(Ordering::SeqCst);
+        loop {
+            items.extend(pool.drain());
+            // Guards stop counting themselves once their item is stored, so
+            // the drain above got every item returned before this.
+            if pool.in_use.load(Ordering::Acquire) == 0 {
+                break;
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            lock = pool.returned.wait_timeout(lock, deadline - now).unwrap().0;
+        }
+        pool.waiters.fetch_sub(1, Ordering::Relaxed);
+        drop(lock);
+        items
+    }
+
+    /// Remove and return every stored item for which `pred` returns `true`.
+    ///
+    /// Like `transform_all`, the pool is drained while `pred` runs, so calls to
+    /// `get` made in the meantime find the pool empty.
+    pub fn drain_filter<F>(&self, pred: F) -> Vec<T>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let pool = &self.internal;
+        let items = pool.drain();
+        let (drained, kept): (Vec<T>, Vec<T>) = items.into_iter().partition(|item| pred(item));
+        for item in kept {
+            pool.enqueue(item);
+        }
+        pool.notify(true);
+        drained
+    }
+
+    /// Clear a batch of items and store them back inside the pool.
+    ///
+    /// Items which don't fit within the pool's capacity are dropped.
+    pub fn recycle_batch(&self, items: Vec<T>) {
+        let pool = &self.internal;
+        let mut stored = false;
+        for item in items {
+            stored |= pool.enqueue(pool.reset(item));
+        }
+        if stored {
+            pool.notify(true);
         }
     }
 
     /// Store an item back inside the pool.
-    fn push(&self, mut item: T) {
-        (*self.internal.clear)(&mut item);
-        self.internal.queue.push(item);
+    fn push(&self, item: T) {
+        #[cfg(feature = "tokio")]
+        {
+            if let Some(async_clear) = &self.internal.async_clear {
+                return (async_clear.spawn)(self.clone(), item);
+            }
+        }
+        let item = self.internal.reset(item);
+        match &self.internal.stage {
+            Some(stage) => stage(self, item),
+            None => self.internal.store(item),
+        }
+    }
+}
+
+/// Items of `Copy` types up to this size are cheaper to create than to pool.
+const COPY_THRESHOLD: usize = 256;
+
+impl<T: Default + 'static> Pool<T> {
+    /// Create a new Pool of default values, which are reset by overwriting
+    /// them with `T::default()` before they're returned to the pool.
+    ///
+    /// This drops whatever the item owned, including any allocations, so it's
+    /// best suited to items which are cheap to create but need a known state.
+    pub fn reset_to_default() -> Pool<T> {
+        Pool::with_clear(T::default, |item| *item = T::default())
+    }
+}
+
+impl<T: PartialEq> Pool<T> {
+    /// Create a new Pool which stores up to `capacity` items and never stores
+    /// two equal items at once.
+    ///
+    /// Returned items which are equal to a stored item are dropped. See
+    /// `Builder::dedup` for the cost of this.
+    pub fn with_dedup<C, D>(create: C, clear: D, capacity: usize) -> Pool<T>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+        D: Fn(&mut T) + Send + Sync + 'static,
+    {
+        Pool::builder(create)
+            .clear(clear)
+            .capacity(capacity)
+            .dedup()
+            .build()
+    }
+}
+
+impl Pool<()> {
+    /// Whether pooling items of type `T` is likely to be faster than creating
+    /// them directly, as in `Pool::is_beneficial_for::<u64>()`.
+    ///
+    /// `Copy` types don't own any allocations, and moving them in and out of
+    /// the queue copies them just like creating them does. Only large items,
+    /// or items which are expensive to create, benefit from pooling.
+    pub const fn is_beneficial_for<T: Copy>() -> bool {
+        mem::size_of::<T>() > COPY_THRESHOLD
+    }
+}
+
+impl<T: Copy + Default> Pool<T> {
+    /// Get an item from the pool, skipping the queue entirely if pooling `T`
+    /// isn't beneficial.
+    ///
+    /// On the fast path the item is `T::default()`, without calling the
+    /// create function, and it's dropped instead of recycled. It counts
+    /// towards `in_use` like any other guard, but isn't reported to
+    /// `Builder::on_get`, `Builder::collect_stats_into` or
+    /// `Builder::alloc_budget`. Pools with a growth policy other than
+    /// `GrowthPolicy::AlwaysAllocate` always use the queue.
+    #[track_caller]
+    pub fn get_copy<'a>(&'a self) -> ItemGuard<'a, T> {
+        if Pool::is_beneficial_for::<T>()
+            || self.internal.growth_policy != GrowthPolicy::AlwaysAllocate
+        {
+            return self.get();
+        }
+        let origin = self.internal.check_out_untracked();
+        let mut guard = ItemGuard::with_origin(self, T::default(), origin);
+        guard.on_drop = OnDrop::Discard;
+        guard
+    }
+}
+
+impl<T: Send + 'static> Pool<T> {
+    /// Create `count` new items on a background thread and store them in the
+    /// pool.
+    ///
+    /// The pool can be used while it's being filled. Join the returned handle
+    /// to wait until it's done.
+    pub fn prefill_background(&self, count: usize) -> JoinHandle<()> {
+        let pool = self.clone();
+        thread::spawn(move || pool.prefill(count))
+    }
+}
+
+impl<C, T> From<(C, Vec<T>)> for Pool<T>
+where
+    C: Fn() -> T + Send + Sync + 'static,
+{
+    /// Create a new Pool from an initializer function, seeded with `items`.
+    fn from((create, items): (C, Vec<T>)) -> Pool<T> {
+        let pool = Pool::new(create);
+        for item in items {
+            pool.internal.queue.push(item);
+        }
+        pool
     }
 }
 
@@ -147,23 +1188,170 @@ impl<T> Clone for Pool<T> {
     }
 }
 
+/// Whether an item from `Pool::get_checked` was newly created or reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    /// The item was just created by the pool's initializer function.
+    Fresh,
+    /// The item was used before and returned to the pool.
+    Reused,
+}
+
+/// What happens to an item when its guard is dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OnDrop {
+    /// Clear the item and return it to the pool.
+    Recycle,
+    /// Return the item to the pool without clearing it.
+    Store,
+    /// Drop the item.
+    Discard,
+}
+
 /// RAII structure used to reintroduce an item into the pool when dropped.
+///
+/// A guard can be sent to another thread if the item is `Send`, but it can
+/// only be shared between threads if the item is `Sync`:
+///
+/// ```compile_fail
+/// use std::cell::Cell;
+///
+/// let pool = remem::Pool::new(|| Cell::new(0));
+/// let item = pool.get();
+/// std::thread::scope(|s| {
+///     s.spawn(|| item.get());
+/// });
+/// ```
 pub struct ItemGuard<'a, T> {
-    item: Option<T>,
+    item: ManuallyDrop<T>,
     pool: &'a Pool<T>,
+    on_drop: OnDrop,
+    origin: origin::Origin,
+}
+
+impl<'a, T> ItemGuard<'a, T> {
+    /// Check out an item, which was either reused from the queue (a hit) or
+    /// newly created.
+    #[track_caller]
+    fn new(pool: &'a Pool<T>, item: T, hit: bool) -> Self {
+        let origin = pool.internal.check_out(&item, hit);
+        ItemGuard::with_origin(pool, item, origin)
+    }
+
+    /// Wrap an item which was already checked out at `origin`.
+    fn with_origin(pool: &'a Pool<T>, item: T, origin: origin::Origin) -> Self {
+        ItemGuard {
+            item: ManuallyDrop::new(item),
+            pool,
+            on_drop: OnDrop::Recycle,
+            origin,
+        }
+    }
+
+    /// Take the item out of the guard, storing a newly created item in the
+    /// pool in its place.
+    ///
+    /// This keeps the pool warm while handing ownership of the item to the
+    /// caller.
+    pub fn take_and_refill(self) -> T {
+        let pool = &self.pool.internal;
+        pool.store((*pool.create)());
+        self.into_item()
+    }
+
+    /// Take the item out of the guard without returning it to the pool.
+    fn into_item(self) -> T {
+        self.take_item().0
+    }
+
+    /// Take the item out of the guard, which stays checked out until the
+    /// returned `CheckIn` is dropped.
+    fn take_item(self) -> (T, CheckIn<'a, T>) {
+        let mut guard = ManuallyDrop::new(self);
+        // SAFETY: the guard is never dropped, so the item isn't taken again.
+        #[allow(unsafe_code)]
+        let item = unsafe { ManuallyDrop::take(&mut guard.item) };
+        (item, CheckIn(guard))
+    }
+
+    /// Record that the item is no longer checked out.
+    fn release(&self) {
+        self.pool.internal.check_in(&self.origin);
+    }
+
+    /// Lend out the item for as long as the returned reference lives.
+    ///
+    /// This is the same as `&mut **guard`, but reads better when passing the
+    /// item to a function which takes `&mut T`, and the guard stays usable
+    /// afterwards.
+    pub fn reborrow(&mut self) -> &mut T {
+        self
+    }
+
+    /// Replace the item with `new`, returning the old item.
+    ///
+    /// The returned item is no longer managed by the pool; `new` is returned to
+    /// the pool instead when the guard is dropped.
+    pub fn swap(&mut self, new: T) -> T {
+        mem::replace(&mut self.item, new)
+    }
+
+    /// Replace the item with the result of `f`, returning the old item.
+    ///
+    /// Like `swap`, the returned item is no longer managed by the pool, and
+    /// the new one is returned to the pool when the guard is dropped.
+    pub fn replace_with<F: FnOnce(&T) -> T>(&mut self, f: F) -> T {
+        let new = f(self);
+        self.swap(new)
+    }
+
+    /// Run `f` on the item, then return it to the pool.
+    ///
+    /// This makes the point where the item is recycled explicit.
+    pub fn with<R, F: FnOnce(&mut T) -> R>(mut self, f: F) -> R {
+        f(&mut self)
+    }
+}
+
+/// A guard whose item was taken out with `ItemGuard::take_item`, which
+/// records that the item is no longer checked out once it's dropped.
+struct CheckIn<'a, T>(ManuallyDrop<ItemGuard<'a, T>>);
+
+impl<T> Drop for CheckIn<'_, T> {
+    fn drop(&mut self) {
+        self.0.release();
+    }
 }
 
 impl<T: Debug> Debug for ItemGuard<'_, T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("ItemGuard")
-            .field("item", &self.item)
-            .finish()
+        let mut f = f.debug_struct("ItemGuard");
+        f.field("item", &*self.item);
+        if let Some(location) = self.origin.location() {
+            f.field("checked_out_at", &format_args!("{}", location));
+        }
+        f.finish()
     }
 }
 
 impl<'a, T> Drop for ItemGuard<'a, T> {
     fn drop(&mut self) {
-        self.pool.push(self.item.take().unwrap())
+        // SAFETY: this is the only place the item is taken out of a guard
+        // which is dropped, and `drop` only runs once.
+        #[allow(unsafe_code)]
+        let item = unsafe { ManuallyDrop::take(&mut self.item) };
+        let pool = &self.pool.internal;
+        if let (Some(stats), false) = (&pool.stats, self.on_drop == OnDrop::Discard) {
+            stats.record_return();
+        }
+        match self.on_drop {
+            OnDrop::Recycle => self.pool.push(item),
+            OnDrop::Store => self.pool.internal.store(item),
+            OnDrop::Discard => {}
+        }
+        // Only stop counting the item once it's stored, so `drain_with_wait`
+        // can't see no guards left while the item is on its way back.
+        self.release();
     }
 }
 
@@ -171,12 +1359,46 @@ impl<'a, T> Deref for ItemGuard<'a, T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        self.item.as_ref().unwrap()
+        &self.item
     }
 }
 
 impl<'a, T> DerefMut for ItemGuard<'a, T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        self.item.as_mut().unwrap()
+        &mut self.item
+    }
+}
+
+impl<'a, A, T: Extend<A>> Extend<A> for ItemGuard<'a, T> {
+    fn extend<I: IntoIterator<Item = A>>(&mut self, iter: I) {
+        (**self).extend(iter)
+    }
+}
+
+impl<'a, T: io::Write> ItemGuard<'a, T> {
+    /// Returns the item as a `&mut dyn Write`, for APIs which take a writer
+    /// without naming its type.
+    ///
+    /// `&mut *guard` coerces to `&mut dyn Write` too; this spells it out.
+    pub fn as_dyn_write(&mut self) -> &mut dyn io::Write {
+        &mut **self
+    }
+}
+
+impl<'a, T: io::Write> io::Write for ItemGuard<'a, T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (**self).write(buf)
+    }
+
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        (**self).write_vectored(bufs)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        (**self).write_all(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (**self).flush()
     }
 }
@@ -0,0 +1,74 @@
+use crate::ItemGuard;
+use std::fmt::{self, Debug};
+use std::ops::{Deref, DerefMut};
+
+/// A value derived from a pooled item, which keeps the item checked out.
+///
+/// The item is returned to the pool when this is dropped. Created by
+/// `ItemGuard::map` and `ItemGuard::try_map`.
+pub struct MappedGuard<'a, T, U> {
+    guard: ItemGuard<'a, T>,
+    value: U,
+}
+
+impl<'a, T, U> MappedGuard<'a, T, U> {
+    /// Returns the item the value was derived from.
+    pub fn item(&self) -> &T {
+        &self.guard
+    }
+
+    /// Split into the original guard and the derived value.
+    pub fn into_parts(self) -> (ItemGuard<'a, T>, U) {
+        (self.guard, self.value)
+    }
+}
+
+impl<T: Debug, U: Debug> Debug for MappedGuard<'_, T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MappedGuard")
+            .field("item", &*self.guard)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl<'a, T, U> Deref for MappedGuard<'a, T, U> {
+    type Target = U;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<'a, T, U> DerefMut for MappedGuard<'a, T, U> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<'a, T> ItemGuard<'a, T> {
+    /// Derive a value from the item, keeping the item checked out for as long
+    /// as the value is in use.
+    pub fn map<U, F>(mut self, f: F) -> MappedGuard<'a, T, U>
+    where
+        F: FnOnce(&mut T) -> U,
+    {
+        let value = f(&mut self);
+        MappedGuard { guard: self, value }
+    }
+
+    /// Try to derive a value from the item, keeping the item checked out for
+    /// as long as the value is in use.
+    ///
+    /// If `f` fails, the error is returned along with the original guard, so
+    /// the item isn't lost.
+    pub fn try_map<U, E, F>(mut self, f: F) -> Result<MappedGuard<'a, T, U>, (Self, E)>
+    where
+        F: FnOnce(&mut T) -> Result<U, E>,
+    {
+        match f(&mut self) {
+            Ok(value) => Ok(MappedGuard { guard: self, value }),
+            Err(err) => Err((self, err)),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::Pool;
+use std::ops::{Deref, DerefMut};
+
+/// A pool whose items carry metadata which survives being reused.
+///
+/// Created by `Pool::with_meta`.
+pub type MetaPool<M, T> = Pool<WithMeta<M, T>>;
+
+/// An item along with metadata which isn't reset when the item is cleared.
+///
+/// This dereferences to the item. Use `meta` and `meta_mut` to access the
+/// metadata, which starts out as `M::default()` for newly created items.
+#[derive(Debug)]
+pub struct WithMeta<M, T> {
+    meta: M,
+    item: T,
+}
+
+impl<M, T> WithMeta<M, T> {
+    /// Returns the metadata.
+    pub fn meta(&self) -> &M {
+        &self.meta
+    }
+
+    /// Returns the metadata mutably.
+    pub fn meta_mut(&mut self) -> &mut M {
+        &mut self.meta
+    }
+
+    /// Returns the metadata and the item mutably at the same time.
+    pub fn parts_mut(&mut self) -> (&mut M, &mut T) {
+        (&mut self.meta, &mut self.item)
+    }
+
+    /// Unwrap the metadata and the item.
+    pub fn into_parts(self) -> (M, T) {
+        (self.meta, self.item)
+    }
+}
+
+impl<M, T> Deref for WithMeta<M, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.item
+    }
+}
+
+impl<M, T> DerefMut for WithMeta<M, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.item
+    }
+}
+
+impl<M: Default, T> Pool<WithMeta<M, T>> {
+    /// Create a new Pool whose items carry metadata, from an initializer
+    /// function and a clear function.
+    ///
+    /// Only the item is cleared before it's returned to the pool; the
+    /// metadata is kept as-is.
+    pub fn with_meta<C, D>(create: C, clear: D) -> MetaPool<M, T>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+        D: Fn(&mut T) + Send + Sync + 'static,
+    {
+        Pool::with_clear(
+            move || WithMeta {
+                meta: M::default(),
+                item: create(),
+            },
+            move |item| clear(&mut item.item),
+        )
+    }
+}
@@ -0,0 +1,178 @@
+//! A pool sharded by NUMA node.
+//!
+//! On multi-socket machines memory is attached to a specific node, and
+//! accessing another node's memory is slower. `NumaPool` keeps a separate
+//! `Pool` per node and hands out items from the node the calling thread is
+//! running on, so reused buffers are usually local.
+
+use crate::{ItemGuard, Pool};
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+/// A pool which keeps items allocated on each NUMA node separate.
+///
+/// Items return to the pool of the node they were checked out from when their
+/// guard is dropped, since their memory stays on the node it was first
+/// allocated on. When the local node's pool is empty, `get` steals an item
+/// from another node before creating a new one.
+///
+/// The node topology is only detected on Linux; elsewhere all threads use a
+/// single node.
+pub struct NumaPool<T> {
+    nodes: Vec<Pool<T>>,
+    cpu_nodes: Vec<usize>,
+}
+
+impl<T> Debug for NumaPool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NumaPool")
+            .field("nodes", &self.nodes)
+            .finish()
+    }
+}
+
+impl<T> NumaPool<T> {
+    /// Create a new NumaPool from an initializer function, with a pool for
+    /// every NUMA node on this machine.
+    pub fn new<C>(create: C) -> NumaPool<T>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+    {
+        let cpu_nodes = topology::cpu_nodes();
+        let nodes = cpu_nodes.iter().max().map_or(1, |max| max + 1);
+        NumaPool::with_topology(create, nodes, cpu_nodes)
+    }
+
+    /// Create a new NumaPool from an initializer function with `nodes` pools,
+    /// regardless of the actual topology. Threads are assigned to the pool of
+    /// their current node modulo `nodes`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nodes` is 0.
+    pub fn with_nodes<C>(create: C, nodes: usize) -> NumaPool<T>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+    {
+        assert!(nodes > 0, "a NumaPool needs at least one node");
+        NumaPool::with_topology(create, nodes, topology::cpu_nodes())
+    }
+
+    /// Create a new NumaPool with `nodes` pools, given the node of every CPU.
+    fn with_topology<C>(create: C, nodes: usize, cpu_nodes: Vec<usize>) -> NumaPool<T>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+    {
+        let create = Arc::new(create);
+        NumaPool {
+            nodes: (0..nodes)
+                .map(|_| {
+                    let create = create.clone();
+                    Pool::new(move || create())
+                })
+                .collect(),
+            cpu_nodes,
+        }
+    }
+
+    /// Returns the number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the pool for node `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= node_count()`.
+    pub fn node(&self, index: usize) -> &Pool<T> {
+        &self.nodes[index]
+    }
+
+    /// Returns the node the calling thread is currently running on.
+    pub fn current_node(&self) -> usize {
+        let node = topology::current_cpu()
+            .and_then(|cpu| self.cpu_nodes.get(cpu).copied())
+            .unwrap_or(0);
+        node % self.nodes.len()
+    }
+
+    /// Get an item from the current node's pool, stealing one from another
+    /// node if it's empty.
+    #[track_caller]
+    pub fn get<'a>(&'a self) -> ItemGuard<'a, T> {
+        let index = self.current_node();
+        let local = &self.nodes[index];
+        if let Some(item) = local.internal.pop() {
+            return ItemGuard::new(local, item, true);
+        }
+        let count = self.nodes.len();
+        for offset in 1..count {
+            let pool = &self.nodes[(index + offset) % count];
+            if let Some(item) = pool.internal.pop() {
+                return ItemGuard::new(pool, item, true);
+            }
+        }
+        local.get()
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod topology {
+    use std::fs;
+
+    /// Returns the node of every CPU, indexed by CPU number.
+    pub(super) fn cpu_nodes() -> Vec<usize> {
+        let mut cpu_nodes = Vec::new();
+        for node in 0.. {
+            let path = format!("/sys/devices/system/node/node{}/cpulist", node);
+            let list = match fs::read_to_string(path) {
+                Ok(list) => list,
+                Err(_) => break,
+            };
+            for cpu in parse_cpu_list(&list) {
+                if cpu >= cpu_nodes.len() {
+                    cpu_nodes.resize(cpu + 1, 0);
+                }
+                cpu_nodes[cpu] = node;
+            }
+        }
+        cpu_nodes
+    }
+
+    /// Parse a list of CPU ranges such as `0-3,8-11`.
+    fn parse_cpu_list(list: &str) -> impl Iterator<Item = usize> + '_ {
+        list.trim()
+            .split(',')
+            .filter_map(|range| {
+                let mut bounds = range.splitn(2, '-').map(|n| n.parse::<usize>().ok());
+                let start = bounds.next()??;
+                let end = bounds.next().unwrap_or(Some(start))?;
+                Some(start..=end)
+            })
+            .flatten()
+    }
+
+    /// Returns the CPU the calling thread is currently running on.
+    #[allow(unsafe_code)]
+    pub(super) fn current_cpu() -> Option<usize> {
+        // SAFETY: `sched_getcpu` has no preconditions.
+        let cpu = unsafe { libc::sched_getcpu() };
+        if cpu < 0 {
+            None
+        } else {
+            Some(cpu as usize)
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod topology {
+    pub(super) fn cpu_nodes() -> Vec<usize> {
+        Vec::new()
+    }
+
+    pub(super) fn current_cpu() -> Option<usize> {
+        None
+    }
+}
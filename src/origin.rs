@@ -0,0 +1,158 @@
+//! Where guards were checked out, for leak diagnostics.
+//!
+//! Checkout locations are only recorded with the `leak_detection` feature.
+//! Without it these types are empty and their methods do nothing, so
+//! checkouts don't pay for it. Items' identities and how long guards are held
+//! are only recorded in debug builds, for pools which asked for them.
+
+#[cfg(feature = "leak_detection")]
+use crate::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "leak_detection")]
+use std::collections::HashMap;
+#[cfg(debug_assertions)]
+use std::collections::HashSet;
+use std::panic::Location;
+#[cfg(any(debug_assertions, feature = "leak_detection"))]
+use std::sync::Mutex;
+use std::time::Duration;
+#[cfg(debug_assertions)]
+use std::time::Instant;
+
+/// The checkout locations of a pool's live guards.
+pub(crate) struct Origins {
+    #[cfg(feature = "leak_detection")]
+    next: AtomicUsize,
+    #[cfg(feature = "leak_detection")]
+    live: Mutex<HashMap<usize, &'static Location<'static>>>,
+    /// The identities of the live items, for pools which check for items
+    /// being issued twice.
+    #[cfg(debug_assertions)]
+    issued: Mutex<HashSet<usize>>,
+}
+
+/// Where a single guard was checked out.
+pub(crate) struct Origin {
+    #[cfg(feature = "leak_detection")]
+    id: usize,
+    #[cfg(feature = "leak_detection")]
+    location: &'static Location<'static>,
+    #[cfg(debug_assertions)]
+    checked_out_at: Option<Instant>,
+    #[cfg(debug_assertions)]
+    identity: Option<usize>,
+}
+
+/// Warns about guards which are held for too long.
+pub(crate) struct MaxHold {
+    limit: Duration,
+    warn: Box<dyn Fn(Duration) + Send + Sync>,
+}
+
+impl Origins {
+    pub(crate) fn new() -> Origins {
+        Origins {
+            #[cfg(feature = "leak_detection")]
+            next: AtomicUsize::new(0),
+            #[cfg(feature = "leak_detection")]
+            live: Mutex::new(HashMap::new()),
+            #[cfg(debug_assertions)]
+            issued: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Record a checkout at the caller's location, of the item with
+    /// `identity` if the pool keeps track of them, and when it happened if
+    /// `timed`.
+    ///
+    /// Panics if an item with the same identity is already checked out.
+    #[track_caller]
+    pub(crate) fn check_out(&self, _identity: Option<usize>, _timed: bool) -> Origin {
+        #[cfg(debug_assertions)]
+        {
+            if let Some(identity) = _identity {
+                let fresh = self.issued.lock().unwrap().insert(identity);
+                assert!(
+                    fresh,
+                    "item {:#x} was issued while already checked out",
+                    identity
+                );
+            }
+        }
+        Origin {
+            #[cfg(feature = "leak_detection")]
+            id: self.record(Location::caller()),
+            #[cfg(feature = "leak_detection")]
+            location: Location::caller(),
+            #[cfg(debug_assertions)]
+            checked_out_at: if _timed { Some(Instant::now()) } else { None },
+            #[cfg(debug_assertions)]
+            identity: _identity,
+        }
+    }
+
+    /// Record a live guard checked out at `location`, returning its id.
+    #[cfg(feature = "leak_detection")]
+    fn record(&self, location: &'static Location<'static>) -> usize {
+        let id = self.next.fetch_add(1, Ordering::Relaxed);
+        self.live.lock().unwrap().insert(id, location);
+        id
+    }
+
+    /// Forget a checkout once its guard has been dropped.
+    pub(crate) fn check_in(&self, _origin: &Origin) {
+        #[cfg(feature = "leak_detection")]
+        self.live.lock().unwrap().remove(&_origin.id);
+        #[cfg(debug_assertions)]
+        {
+            if let Some(identity) = _origin.identity {
+                self.issued.lock().unwrap().remove(&identity);
+            }
+        }
+    }
+
+    /// The locations of all guards which haven't been dropped, oldest first.
+    pub(crate) fn live(&self) -> Vec<&'static Location<'static>> {
+        #[cfg(feature = "leak_detection")]
+        {
+            let live = self.live.lock().unwrap();
+            let mut live: Vec<_> = live.iter().map(|(&id, &location)| (id, location)).collect();
+            live.sort_by_key(|&(id, _)| id);
+            live.into_iter().map(|(_, location)| location).collect()
+        }
+        #[cfg(not(feature = "leak_detection"))]
+        Vec::new()
+    }
+}
+
+impl Origin {
+    pub(crate) fn location(&self) -> Option<&'static Location<'static>> {
+        #[cfg(feature = "leak_detection")]
+        return Some(self.location);
+        #[cfg(not(feature = "leak_detection"))]
+        None
+    }
+
+    /// Returns how long the guard has been checked out for, if the checkout
+    /// was timed.
+    fn held(&self) -> Option<Duration> {
+        #[cfg(debug_assertions)]
+        return self.checked_out_at.map(|at| at.elapsed());
+        #[cfg(not(debug_assertions))]
+        None
+    }
+}
+
+impl MaxHold {
+    pub(crate) fn new(limit: Duration, warn: Box<dyn Fn(Duration) + Send + Sync>) -> MaxHold {
+        MaxHold { limit, warn }
+    }
+
+    /// Warn if the guard checked out at `origin` was held for too long.
+    pub(crate) fn check(&self, origin: &Origin) {
+        if let Some(held) = origin.held() {
+            if held > self.limit {
+                (self.warn)(held);
+            }
+        }
+    }
+}
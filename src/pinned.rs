@@ -0,0 +1,72 @@
+use crate::{ItemGuard, Pool};
+use std::fmt::{self, Debug};
+use std::ops::Deref;
+use std::pin::Pin;
+
+/// RAII structure giving pinned access to a pooled item.
+///
+/// Items of pinned pools are boxed once when they're created and never move
+/// afterwards: not while they're checked out, and not while they're stored in
+/// the pool, since only the box is moved in and out of the queue. This makes
+/// them suitable for self-referential types. Use `as_mut` to get a
+/// `Pin<&mut T>`.
+///
+/// Created by `Pool::get_pinned`.
+pub struct PinnedGuard<'a, T> {
+    guard: ItemGuard<'a, Pin<Box<T>>>,
+}
+
+impl<T: Debug> Debug for PinnedGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PinnedGuard")
+            .field("item", &**self)
+            .finish()
+    }
+}
+
+impl<'a, T> PinnedGuard<'a, T> {
+    /// Get a pinned mutable reference to the item.
+    pub fn as_mut(&mut self) -> Pin<&mut T> {
+        self.guard.as_mut()
+    }
+}
+
+impl<'a, T> Deref for PinnedGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<T> Pool<Pin<Box<T>>> {
+    /// Create a new Pool of pinned items from an initializer function.
+    ///
+    /// See `PinnedGuard`.
+    pub fn pinned<C>(create: C) -> Pool<Pin<Box<T>>>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+    {
+        Pool::new(move || Box::pin(create()))
+    }
+
+    /// Create a new Pool of pinned items from an initializer function and a
+    /// clear function, which resets items in place.
+    pub fn pinned_with_clear<C, D>(create: C, clear: D) -> Pool<Pin<Box<T>>>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+        D: Fn(Pin<&mut T>) + Send + Sync + 'static,
+    {
+        Pool::with_clear(move || Box::pin(create()), move |item| clear(item.as_mut()))
+    }
+
+    /// Get a pinned item from the pool.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `get`.
+    #[track_caller]
+    pub fn get_pinned<'a>(&'a self) -> PinnedGuard<'a, T> {
+        PinnedGuard { guard: self.get() }
+    }
+}
@@ -0,0 +1,64 @@
+//! A process-wide list of named pools, for dumping the state of every pool in
+//! one place.
+//!
+//! Pools are added with `Pool::register`. The registry only holds weak
+//! references, so a pool disappears from it once all its handles are dropped.
+
+use crate::atomic::Ordering;
+use crate::{Internal, Pool};
+use std::sync::{Arc, Mutex, Weak};
+
+static REGISTRY: Mutex<Vec<(String, Weak<dyn Health>)>> = Mutex::new(Vec::new());
+
+/// A snapshot of a registered pool's state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolHealth {
+    /// The number of items stored in the pool.
+    pub len: usize,
+    /// The number of items checked out of the pool.
+    pub in_use: usize,
+    /// The maximum number of items the pool stores.
+    pub capacity: usize,
+}
+
+/// Lets the registry hold pools of any item type.
+trait Health: Send + Sync {
+    fn health(&self) -> PoolHealth;
+}
+
+impl<T: Send> Health for Internal<T> {
+    fn health(&self) -> PoolHealth {
+        PoolHealth {
+            len: self.queue.len(),
+            in_use: self.in_use.load(Ordering::Relaxed),
+            capacity: self.capacity.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl<T: Send + 'static> Pool<T> {
+    /// Add this pool to the registry under `name`.
+    ///
+    /// Names don't need to be unique. Registering the same pool twice lists it
+    /// twice.
+    pub fn register(&self, name: impl Into<String>) {
+        let internal = Arc::downgrade(&self.internal);
+        let mut registry = REGISTRY.lock().unwrap();
+        registry.push((name.into(), internal));
+    }
+}
+
+/// Returns the name and state of every registered pool which still exists, in
+/// the order they were registered.
+pub fn report() -> Vec<(String, PoolHealth)> {
+    let mut registry = REGISTRY.lock().unwrap();
+    let mut report = Vec::with_capacity(registry.len());
+    registry.retain(|(name, pool)| match pool.upgrade() {
+        Some(pool) => {
+            report.push((name.clone(), pool.health()));
+            true
+        }
+        None => false,
+    });
+    report
+}
@@ -0,0 +1,66 @@
+use crate::atomic::{AtomicUsize, Ordering};
+use crate::{ItemGuard, Pool};
+use std::array;
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+/// A fixed number of pools which `get` cycles through in turn.
+///
+/// This spreads checkouts evenly over the pools without looking up which
+/// thread is calling, which suits a fixed number of workers. Items are
+/// returned to the pool they were checked out of.
+pub struct PoolRing<T, const N: usize> {
+    pools: [Pool<T>; N],
+    next: AtomicUsize,
+}
+
+impl<T, const N: usize> Debug for PoolRing<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoolRing")
+            .field("pools", &self.pools)
+            .field("next", &self.next.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+impl<T, const N: usize> PoolRing<T, N> {
+    /// Create a new PoolRing of `N` pools sharing an initializer function.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is 0.
+    pub fn new<C>(create: C) -> PoolRing<T, N>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+    {
+        assert!(N > 0, "a PoolRing needs at least one pool");
+        let create = Arc::new(create);
+        PoolRing {
+            pools: array::from_fn(|_| {
+                let create = create.clone();
+                Pool::new(move || create())
+            }),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the pool at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn pool(&self, index: usize) -> &Pool<T> {
+        &self.pools[index]
+    }
+
+    /// Get an item from the next pool in turn.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `Pool::get`.
+    #[track_caller]
+    pub fn get<'a>(&'a self) -> ItemGuard<'a, T> {
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % N;
+        self.pools[index].get()
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{ItemGuard, Pool};
+use std::fmt::{self, Debug};
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+/// Picks the pool an item is returned to.
+type Route<'a, T> = Box<dyn Fn(&T) -> &'a Pool<T> + 'a>;
+
+/// A guard which picks the pool its item is returned to when it's dropped.
+///
+/// Created by `Pool::get_routed`. The routing function sees the item before
+/// it's cleared, so it can route on state like the buffer's size.
+pub struct RoutedGuard<'a, T> {
+    guard: Option<ItemGuard<'a, T>>,
+    route: Route<'a, T>,
+}
+
+impl<T: Debug> Debug for RoutedGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RoutedGuard")
+            .field("item", &**self)
+            .field("route", &"Box<dyn Fn(&T) -> &Pool<T>>")
+            .finish()
+    }
+}
+
+impl<T> Pool<T> {
+    /// Get an item from the pool, which is returned to the pool chosen by
+    /// `route` once the guard is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `get`.
+    #[track_caller]
+    pub fn get_routed<'a, R>(&'a self, route: R) -> RoutedGuard<'a, T>
+    where
+        R: Fn(&T) -> &'a Pool<T> + 'a,
+    {
+        RoutedGuard {
+            guard: Some(self.get()),
+            route: Box::new(route),
+        }
+    }
+}
+
+impl<T> Deref for RoutedGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.guard.as_ref().unwrap()
+    }
+}
+
+impl<T> DerefMut for RoutedGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.guard.as_mut().unwrap()
+    }
+}
+
+impl<T> Drop for RoutedGuard<'_, T> {
+    fn drop(&mut self) {
+        let guard = self.guard.take().unwrap();
+        let target = (self.route)(&guard);
+        if Arc::ptr_eq(&target.internal, &guard.pool.internal) {
+            return;
+        }
+        target.push(guard.into_item());
+    }
+}
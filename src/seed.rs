@@ -0,0 +1,55 @@
+use crate::Pool;
+use std::error::Error;
+use std::fmt::{self, Display};
+
+/// The error returned when seeding a bounded pool fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeedError {
+    /// More items were given than fit in the pool.
+    TooManyItems {
+        /// The capacity of the pool.
+        capacity: usize,
+    },
+}
+
+impl Display for SeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedError::TooManyItems { capacity } => {
+                write!(
+                    f,
+                    "more than {} items were given to seed the pool",
+                    capacity
+                )
+            }
+        }
+    }
+}
+
+impl Error for SeedError {}
+
+impl<T> Pool<T> {
+    /// Create a new Pool which keeps at most `capacity` items, seeded with
+    /// `items`.
+    ///
+    /// Returns an error if `items` yields more than `capacity` items, rather
+    /// than dropping the ones which don't fit.
+    pub fn try_from_bounded<C, I>(
+        create: C,
+        capacity: usize,
+        items: I,
+    ) -> Result<Pool<T>, SeedError>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+        I: IntoIterator<Item = T>,
+    {
+        let pool = Pool::builder(create).capacity(capacity).build();
+        for item in items {
+            if pool.len() >= capacity {
+                return Err(SeedError::TooManyItems { capacity });
+            }
+            pool.internal.queue.push(item);
+        }
+        Ok(pool)
+    }
+}
@@ -0,0 +1,56 @@
+use crate::Pool;
+use std::sync::{Arc, Mutex, TryLockError};
+
+/// Wrap `clear` so it runs on the value inside an `Arc<Mutex<T>>`, without
+/// blocking if the lock is held.
+///
+/// Clear functions run while a guard is being dropped, so waiting for the
+/// lock there could deadlock, for example if the thread dropping the guard
+/// still holds the lock through another clone of the `Arc`. If the lock is
+/// contended the value can't be cleared, so it's left to whoever else holds
+/// it and replaced with a fresh one from `create`. A poisoned lock is cleared
+/// anyway, which is usually what resetting it is for, and then unpoisoned.
+pub fn try_lock_clear<T, C, D>(
+    create: C,
+    clear: D,
+) -> impl Fn(&mut Arc<Mutex<T>>) + Send + Sync + 'static
+where
+    C: Fn() -> T + Send + Sync + 'static,
+    D: Fn(&mut T) + Send + Sync + 'static,
+    T: 'static,
+{
+    move |shared| {
+        let contended = match shared.try_lock() {
+            Ok(mut value) => {
+                clear(&mut value);
+                false
+            }
+            Err(TryLockError::Poisoned(poisoned)) => {
+                clear(&mut poisoned.into_inner());
+                shared.clear_poison();
+                false
+            }
+            Err(TryLockError::WouldBlock) => true,
+        };
+        if contended {
+            *shared = Arc::new(Mutex::new(create()));
+        }
+    }
+}
+
+impl<T> Pool<Arc<Mutex<T>>> {
+    /// Create a new Pool of shared values, cleared with `try_lock_clear`.
+    pub fn shared<C, D>(create: C, clear: D) -> Pool<Arc<Mutex<T>>>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+        D: Fn(&mut T) + Send + Sync + 'static,
+        T: 'static,
+    {
+        let create = Arc::new(create);
+        let replace = Arc::clone(&create);
+        Pool::with_clear(
+            move || Arc::new(Mutex::new(create())),
+            try_lock_clear(move || replace(), clear),
+        )
+    }
+}
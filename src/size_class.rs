@@ -0,0 +1,69 @@
+//! A byte buffer pool with a separate queue per buffer size.
+
+use crate::{ItemGuard, Pool};
+use std::fmt::{self, Debug};
+
+/// A pool of byte buffers split into size classes.
+///
+/// Each class is a separate `Pool` of buffers with at least that many bytes
+/// allocated. `get` picks the smallest class which fits the requested size,
+/// and buffers return to the class they were checked out from. Requests
+/// larger than every class are allocated on demand and dropped afterwards.
+pub struct SizeClassPool {
+    classes: Vec<(usize, Pool<Vec<u8>>)>,
+    oversized: Pool<Vec<u8>>,
+}
+
+impl Debug for SizeClassPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SizeClassPool")
+            .field("classes", &self.classes)
+            .finish()
+    }
+}
+
+impl SizeClassPool {
+    /// Create a new SizeClassPool with a class for each of `sizes`.
+    ///
+    /// The sizes don't need to be sorted, and duplicates are ignored. Buffers
+    /// are cleared before they're returned to the pool.
+    pub fn new(sizes: &[usize]) -> SizeClassPool {
+        let mut sizes = sizes.to_vec();
+        sizes.sort_unstable();
+        sizes.dedup();
+        SizeClassPool {
+            classes: sizes
+                .into_iter()
+                .map(|size| {
+                    let pool = Pool::with_clear(move || Vec::with_capacity(size), Vec::clear);
+                    (size, pool)
+                })
+                .collect(),
+            oversized: Pool::builder(Vec::new).capacity(0).build(),
+        }
+    }
+
+    /// Returns the sizes of the classes, from smallest to largest.
+    pub fn sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.classes.iter().map(|(size, _)| *size)
+    }
+
+    /// Returns the pool of the smallest class which fits `size` bytes, or
+    /// `None` if `size` is larger than every class.
+    pub fn class(&self, size: usize) -> Option<&Pool<Vec<u8>>> {
+        let index = self.classes.partition_point(|(class, _)| *class < size);
+        self.classes.get(index).map(|(_, pool)| pool)
+    }
+
+    /// Get an empty buffer with at least `size` bytes allocated.
+    #[track_caller]
+    pub fn get<'a>(&'a self, size: usize) -> ItemGuard<'a, Vec<u8>> {
+        match self.class(size) {
+            Some(pool) => pool.get(),
+            None => self
+                .oversized
+                .try_get_with(|| Vec::with_capacity(size))
+                .expect("pool is empty"),
+        }
+    }
+}
@@ -0,0 +1,12 @@
+/// Receives events from a pool, for forwarding to a metrics system.
+///
+/// Attach one with `Builder::collect_stats_into`. The methods are called
+/// inline on every checkout and return, so they should be cheap.
+pub trait StatsSink: Send + Sync {
+    /// Called when an item is checked out. `hit` is `true` if the item was
+    /// reused, and `false` if it was newly created.
+    fn record_get(&self, hit: bool);
+
+    /// Called when a guard is dropped and its item handed back to the pool.
+    fn record_return(&self);
+}
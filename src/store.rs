@@ -0,0 +1,183 @@
+//! The queue a `Pool` keeps its items in.
+//!
+//! Miri can't run some of the lock-free code in `crossbeam`, so under Miri
+//! the items are kept in a plain `Mutex<VecDeque<T>>` instead. It has the same
+//! FIFO order and the same interface, which lets users Miri-check code that
+//! uses a pool.
+//!
+//! Pools created with `Pool::with_size_priority` keep their items in a locked
+//! binary heap instead, so the largest item is always taken first.
+
+use crate::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use crossbeam_queue::PopError;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::Mutex;
+
+#[cfg(not(miri))]
+pub(crate) use crossbeam_queue::SegQueue as Queue;
+
+#[cfg(miri)]
+pub(crate) use self::locked::Queue;
+
+/// Returns the size of an item, for pools which hand out the largest first.
+pub(crate) type SizeOf<T> = Box<dyn Fn(&T) -> usize + Send + Sync>;
+
+/// Returns how many bytes an item holds, for pools which keep track of the
+/// memory they hold.
+pub(crate) type Weigh<T> = fn(&T) -> usize;
+
+pub(crate) struct Store<T> {
+    items: Items<T>,
+    weigh: Option<Weigh<T>>,
+    /// The total weight of the stored items, if `weigh` is set.
+    bytes: AtomicUsize,
+}
+
+// The queue is padded to avoid false sharing, and boxing it would add an
+// indirection to every checkout.
+#[allow(clippy::large_enum_variant)]
+enum Items<T> {
+    Queue(Queue<T>),
+    Heap(Heap<T>),
+}
+
+impl<T> Store<T> {
+    pub(crate) fn new(size_of: Option<SizeOf<T>>, weigh: Option<Weigh<T>>) -> Store<T> {
+        let items = match size_of {
+            Some(size_of) => Items::Heap(Heap {
+                items: Mutex::new(BinaryHeap::new()),
+                size_of,
+            }),
+            None => Items::Queue(Queue::new()),
+        };
+        Store {
+            items,
+            weigh,
+            bytes: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the underlying queue, unless the items are kept in a heap.
+    #[cfg(all(feature = "unstable", not(miri)))]
+    pub(crate) fn queue(&self) -> Option<&Queue<T>> {
+        match &self.items {
+            Items::Queue(queue) => Some(queue),
+            Items::Heap(_) => None,
+        }
+    }
+
+    /// Returns the total weight of the stored items, or `None` if the store
+    /// doesn't keep track of it.
+    pub(crate) fn bytes(&self) -> Option<usize> {
+        self.weigh?;
+        Some(self.bytes.load(AtomicOrdering::Relaxed))
+    }
+
+    pub(crate) fn push(&self, item: T) {
+        if let Some(weigh) = self.weigh {
+            self.bytes.fetch_add(weigh(&item), AtomicOrdering::Relaxed);
+        }
+        match &self.items {
+            Items::Queue(queue) => queue.push(item),
+            Items::Heap(heap) => {
+                let size = (heap.size_of)(&item);
+                heap.items.lock().unwrap().push(BySize { size, item });
+            }
+        }
+    }
+
+    pub(crate) fn pop(&self) -> Result<T, PopError> {
+        let item = match &self.items {
+            Items::Queue(queue) => queue.pop()?,
+            Items::Heap(heap) => match heap.items.lock().unwrap().pop() {
+                Some(sized) => sized.item,
+                None => return Err(PopError),
+            },
+        };
+        if let Some(weigh) = self.weigh {
+            self.bytes.fetch_sub(weigh(&item), AtomicOrdering::Relaxed);
+        }
+        Ok(item)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match &self.items {
+            Items::Queue(queue) => queue.len(),
+            Items::Heap(heap) => heap.items.lock().unwrap().len(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        match &self.items {
+            Items::Queue(queue) => queue.is_empty(),
+            Items::Heap(heap) => heap.items.lock().unwrap().is_empty(),
+        }
+    }
+}
+
+struct Heap<T> {
+    items: Mutex<BinaryHeap<BySize<T>>>,
+    size_of: SizeOf<T>,
+}
+
+/// An item along with its size, ordered by size alone.
+struct BySize<T> {
+    size: usize,
+    item: T,
+}
+
+impl<T> PartialEq for BySize<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size
+    }
+}
+
+impl<T> Eq for BySize<T> {}
+
+impl<T> PartialOrd for BySize<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for BySize<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.size.cmp(&other.size)
+    }
+}
+
+#[cfg(miri)]
+mod locked {
+    use crossbeam_queue::PopError;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    pub(crate) struct Queue<T> {
+        items: Mutex<VecDeque<T>>,
+    }
+
+    impl<T> Queue<T> {
+        pub(crate) fn new() -> Queue<T> {
+            Queue {
+                items: Mutex::new(VecDeque::new()),
+            }
+        }
+
+        pub(crate) fn push(&self, item: T) {
+            self.items.lock().unwrap().push_back(item);
+        }
+
+        pub(crate) fn pop(&self) -> Result<T, PopError> {
+            self.items.lock().unwrap().pop_front().ok_or(PopError)
+        }
+
+        pub(crate) fn len(&self) -> usize {
+            self.items.lock().unwrap().len()
+        }
+
+        pub(crate) fn is_empty(&self) -> bool {
+            self.items.lock().unwrap().is_empty()
+        }
+    }
+}
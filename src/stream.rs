@@ -0,0 +1,44 @@
+//! A `Stream` of items checked out of a pool.
+
+use crate::{ItemGuard, Pool};
+use futures_core::Stream;
+use std::fmt::{self, Debug};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A `Stream` which checks an item out of a pool every time it's polled.
+///
+/// For pools which use `GrowthPolicy::FailOnEmpty` or `GrowthPolicy::Block`,
+/// the stream waits while the pool is empty, and is woken up once an item is
+/// returned. Pools which always allocate never wait. The stream never ends.
+pub struct PoolStream<'a, T> {
+    pool: &'a Pool<T>,
+}
+
+impl<T> Debug for PoolStream<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoolStream")
+            .field("pool", &self.pool)
+            .finish()
+    }
+}
+
+impl<T> Pool<T> {
+    /// Returns a `Stream` of items checked out of the pool.
+    ///
+    /// See `PoolStream`.
+    pub fn stream(&self) -> PoolStream<'_, T> {
+        PoolStream { pool: self }
+    }
+}
+
+impl<'a, T> Stream for PoolStream<'a, T> {
+    type Item = ItemGuard<'a, T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let pool = self.pool;
+        pool.internal
+            .poll_acquire(cx)
+            .map(|(item, hit)| Some(ItemGuard::new(pool, item, hit)))
+    }
+}
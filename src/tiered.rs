@@ -0,0 +1,79 @@
+//! Two pools layered on top of each other.
+
+use crate::{ItemGuard, Pool};
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+/// A small first-tier pool backed by a larger second-tier pool.
+///
+/// `get` takes an item from the first tier, then the second, and only creates
+/// a new item if both are empty. Returned items go to the first tier, and
+/// overflow into the second tier once the first is full. Items returned when
+/// both tiers are full are dropped.
+pub struct TieredPool<T> {
+    l1: Pool<T>,
+    l2: Pool<T>,
+}
+
+impl<T> Debug for TieredPool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TieredPool")
+            .field("l1", &self.l1)
+            .field("l2", &self.l2)
+            .finish()
+    }
+}
+
+impl<T> TieredPool<T> {
+    /// Create a new TieredPool from an initializer function, whose tiers
+    /// store up to `l1` and `l2` items respectively.
+    pub fn new<C>(create: C, l1: usize, l2: usize) -> TieredPool<T>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+    {
+        TieredPool::with_clear(create, |_| {}, l1, l2)
+    }
+
+    /// Create a new TieredPool from an initializer function and a clear
+    /// function, whose tiers store up to `l1` and `l2` items respectively.
+    ///
+    /// Items are cleared once, when they're returned to the first tier.
+    pub fn with_clear<C, D>(create: C, clear: D, l1: usize, l2: usize) -> TieredPool<T>
+    where
+        C: Fn() -> T + Send + Sync + 'static,
+        D: Fn(&mut T) + Send + Sync + 'static,
+    {
+        let create = Arc::new(create);
+        let c = create.clone();
+        let l2 = Pool::builder(move || c()).capacity(l2).build();
+        let mut builder = Pool::builder(move || create()).clear(clear).capacity(l1);
+        builder.spill = Some(l2.clone());
+        TieredPool {
+            l1: builder.build(),
+            l2,
+        }
+    }
+
+    /// Returns the first tier.
+    pub fn l1(&self) -> &Pool<T> {
+        &self.l1
+    }
+
+    /// Returns the second tier.
+    pub fn l2(&self) -> &Pool<T> {
+        &self.l2
+    }
+
+    /// Get an item from the first tier, falling back to the second.
+    ///
+    /// The item is returned to the first tier when the guard is dropped,
+    /// wherever it came from.
+    #[track_caller]
+    pub fn get<'a>(&'a self) -> ItemGuard<'a, T> {
+        let item = self.l1.internal.pop().or_else(|| self.l2.internal.pop());
+        match item {
+            Some(item) => ItemGuard::new(&self.l1, item, true),
+            None => self.l1.get(),
+        }
+    }
+}
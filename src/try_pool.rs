@@ -0,0 +1,70 @@
+use crate::{GrowthPolicy, ItemGuard, Pool};
+use std::fmt::{self, Debug};
+
+/// A pool whose items are created by a fallible function.
+///
+/// `get` returns the error from the create function instead of panicking, so
+/// there's no infallible path to call by accident. The guards are the same as
+/// a regular `Pool`'s, except that `ItemGuard::take_and_refill` panics, since
+/// it has no way to report an error.
+pub struct TryPool<T, E> {
+    pool: Pool<T>,
+    create: Box<dyn Fn() -> Result<T, E> + Send + Sync>,
+}
+
+impl<T, E> Debug for TryPool<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TryPool")
+            .field("pool", &self.pool)
+            .field("create", &"Box<dyn Fn() -> Result<T, E> + Send + Sync>")
+            .finish()
+    }
+}
+
+impl<T, E> TryPool<T, E> {
+    /// Create a new TryPool from a fallible initializer function.
+    pub fn new<C>(create: C) -> TryPool<T, E>
+    where
+        C: Fn() -> Result<T, E> + Send + Sync + 'static,
+    {
+        TryPool::with_clear(create, |_| {})
+    }
+
+    /// Create a new TryPool from a fallible initializer function and a clear
+    /// function.
+    pub fn with_clear<C, D>(create: C, clear: D) -> TryPool<T, E>
+    where
+        C: Fn() -> Result<T, E> + Send + Sync + 'static,
+        D: Fn(&mut T) + Send + Sync + 'static,
+    {
+        let pool = Pool::builder(|| panic!("items of a TryPool can't be created infallibly"))
+            .clear(clear)
+            .growth_policy(GrowthPolicy::FailOnEmpty)
+            .build();
+        TryPool {
+            pool,
+            create: Box::new(create),
+        }
+    }
+
+    /// Get an item from the pool, creating a new one if it's empty.
+    ///
+    /// Returns the create function's error if that fails.
+    #[track_caller]
+    pub fn get<'a>(&'a self) -> Result<ItemGuard<'a, T>, E> {
+        match self.pool.internal.pop() {
+            Some(item) => Ok(ItemGuard::new(&self.pool, item, true)),
+            None => (self.create)().map(|item| ItemGuard::new(&self.pool, item, false)),
+        }
+    }
+
+    /// Returns the number of items stored in the pool.
+    pub fn len(&self) -> usize {
+        self.pool.len()
+    }
+
+    /// Returns `true` if there are no items stored in the pool.
+    pub fn is_empty(&self) -> bool {
+        self.pool.is_empty()
+    }
+}
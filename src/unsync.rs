@@ -0,0 +1,151 @@
+//! A single-threaded pool with deterministic ordering.
+//!
+//! `unsync::Pool` stores its items in a `RefCell<Vec<T>>` instead of a
+//! lock-free queue. It can't be shared between threads, but it's cheaper and
+//! always hands out the most recently returned item first, which makes reuse
+//! order predictable in tests.
+//!
+//! It only supports a small part of the interface of `remem::Pool`: `new`,
+//! `with_clear`, `get`, `prefill`, `len`, `is_empty` and `clone`, with guards
+//! which dereference to the item. There's no builder, growth policy or
+//! capacity, so `get` always creates an item when the pool is empty.
+
+use std::cell::RefCell;
+use std::fmt::{self, Debug};
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+struct Internal<T> {
+    items: RefCell<Vec<T>>,
+    create: Box<dyn Fn() -> T>,
+    clear: Box<dyn Fn(&mut T)>,
+}
+
+/// A single-threaded pool of reusable memory.
+pub struct Pool<T> {
+    internal: Rc<Internal<T>>,
+}
+
+impl<T> Debug for Pool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pool")
+            .field("items", &format!("[T; {}]", self.len()))
+            .field("create", &"Box<dyn Fn() -> T>")
+            .field("clear", &"Box<dyn Fn(&mut T)>")
+            .finish()
+    }
+}
+
+impl<T> Pool<T> {
+    /// Create a new Pool from an initializer function.
+    pub fn new<C>(create: C) -> Pool<T>
+    where
+        C: Fn() -> T + 'static,
+    {
+        Pool::with_clear(create, |_| {})
+    }
+
+    /// Create a new Pool from an initializer function and a clear function.
+    ///
+    /// See `remem::Pool::with_clear` for more details.
+    pub fn with_clear<C, D>(create: C, clear: D) -> Pool<T>
+    where
+        C: Fn() -> T + 'static,
+        D: Fn(&mut T) + 'static,
+    {
+        Pool {
+            internal: Rc::new(Internal {
+                items: RefCell::new(Vec::new()),
+                create: Box::new(create),
+                clear: Box::new(clear),
+            }),
+        }
+    }
+
+    /// Get an item from the pool.
+    ///
+    /// The most recently returned item is handed out first.
+    pub fn get<'a>(&'a self) -> ItemGuard<'a, T> {
+        let item = self.internal.items.borrow_mut().pop();
+        ItemGuard {
+            item: Some(item.unwrap_or_else(|| (*self.internal.create)())),
+            pool: self,
+        }
+    }
+
+    /// Create `count` new items and store them in the pool.
+    pub fn prefill(&self, count: usize) {
+        let mut items = self.internal.items.borrow_mut();
+        items.extend((0..count).map(|_| (*self.internal.create)()));
+    }
+
+    /// Returns the number of items stored in the pool.
+    pub fn len(&self) -> usize {
+        self.internal.items.borrow().len()
+    }
+
+    /// Returns `true` if there are no items stored in the pool.
+    pub fn is_empty(&self) -> bool {
+        self.internal.items.borrow().is_empty()
+    }
+
+    /// Store an item back inside the pool.
+    fn push(&self, mut item: T) {
+        (*self.internal.clear)(&mut item);
+        self.internal.items.borrow_mut().push(item);
+    }
+}
+
+impl<T> Clone for Pool<T> {
+    fn clone(&self) -> Self {
+        Pool {
+            internal: self.internal.clone(),
+        }
+    }
+}
+
+/// RAII structure used to reintroduce an item into the pool when dropped.
+pub struct ItemGuard<'a, T> {
+    item: Option<T>,
+    pool: &'a Pool<T>,
+}
+
+impl<T: Debug> Debug for ItemGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ItemGuard")
+            .field("item", &self.item)
+            .finish()
+    }
+}
+
+impl<'a, T> Drop for ItemGuard<'a, T> {
+    fn drop(&mut self) {
+        self.pool.push(self.item.take().unwrap())
+    }
+}
+
+impl<'a, T> Deref for ItemGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.item.as_ref().unwrap()
+    }
+}
+
+impl<'a, T> DerefMut for ItemGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.item.as_mut().unwrap()
+    }
+}
+
+impl<T> crate::Pool<T> {
+    /// Create a new single-threaded Pool from an initializer function.
+    ///
+    /// See the `unsync` module for more details.
+    pub fn new_unsync<C>(create: C) -> Pool<T>
+    where
+        C: Fn() -> T + 'static,
+    {
+        Pool::new(create)
+    }
+}
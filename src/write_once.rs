@@ -0,0 +1,67 @@
+use crate::{ItemGuard, Pool};
+use std::fmt::{self, Debug};
+use std::ops::Deref;
+
+/// RAII structure for items which are filled once and then only read.
+///
+/// The item can be written to once, through `write`. In debug builds a second
+/// call panics, which catches code writing to a buffer after it's been handed
+/// off. Reads are always allowed, but the guard can't be mutably
+/// dereferenced, so every write is spelled out:
+///
+/// ```compile_fail
+/// let pool = remem::Pool::new(Vec::<u8>::new);
+/// let mut item = pool.get_write_once();
+/// item.push(1);
+/// ```
+pub struct WriteOnceGuard<'a, T> {
+    guard: ItemGuard<'a, T>,
+    written: bool,
+}
+
+impl<T: Debug> Debug for WriteOnceGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WriteOnceGuard")
+            .field("item", &*self.guard)
+            .field("written", &self.written)
+            .finish()
+    }
+}
+
+impl<T> Pool<T> {
+    /// Get an item from the pool which can only be written to once.
+    ///
+    /// See `WriteOnceGuard`.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `get`.
+    #[track_caller]
+    pub fn get_write_once<'a>(&'a self) -> WriteOnceGuard<'a, T> {
+        WriteOnceGuard {
+            guard: self.get(),
+            written: false,
+        }
+    }
+}
+
+impl<'a, T> Deref for WriteOnceGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<'a, T> WriteOnceGuard<'a, T> {
+    /// Get mutable access to the item, for the one time it's written to.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if the item was already written to.
+    pub fn write(&mut self) -> &mut T {
+        debug_assert!(!self.written, "the item was already written to");
+        self.written = true;
+        &mut self.guard
+    }
+}
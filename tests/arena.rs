@@ -0,0 +1,22 @@
+#![cfg(feature = "bumpalo")]
+
+use remem::ArenaPool;
+
+#[test]
+fn arena_reuses_memory() {
+    let mut pool = ArenaPool::with_capacity(|| [0u64; 4], 1024);
+    let first: *const [u64; 4] = pool.get();
+    for _ in 0..7 {
+        pool.get()[0] = 1;
+    }
+    let allocated = pool.allocated_bytes();
+
+    pool.reset_arena();
+    let item = pool.get();
+    assert_eq!(*item, [0; 4]);
+    assert_eq!(item as *const _, first);
+    for _ in 0..7 {
+        pool.get();
+    }
+    assert_eq!(pool.allocated_bytes(), allocated);
+}
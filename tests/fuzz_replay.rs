@@ -0,0 +1,32 @@
+mod support;
+
+use support::concurrent;
+
+#[test]
+fn replay_single_thread() {
+    concurrent::run(&[0, 0, 0, 3, 2, 0, 6, 1, 3, 2, 2, 2]);
+}
+
+#[test]
+fn replay_many_threads() {
+    let mut seed = 0x2545_f491_u32;
+    let data: Vec<u8> = (0..4096)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed as u8
+        })
+        .collect();
+    for threads in 0..4 {
+        let mut data = data.clone();
+        data[0] = threads;
+        concurrent::run(&data);
+    }
+}
+
+#[test]
+fn replay_empty() {
+    concurrent::run(&[]);
+    concurrent::run(&[3]);
+}
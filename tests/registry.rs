@@ -0,0 +1,29 @@
+#![cfg(feature = "registry")]
+
+use remem::registry::{self, PoolHealth};
+use remem::Pool;
+
+#[test]
+fn report_registered_pools() {
+    let buffers = Pool::new(Vec::<u8>::new);
+    buffers.register("buffers");
+    let strings = Pool::string(16);
+    strings.register("strings");
+    strings.prefill(2);
+    let _item = buffers.get();
+
+    let report = registry::report();
+    let health = |name: &str| report.iter().find(|(n, _)| n == name).map(|(_, h)| *h);
+    let expected = PoolHealth {
+        len: 0,
+        in_use: 1,
+        capacity: usize::MAX,
+    };
+    assert_eq!(health("buffers"), Some(expected));
+    assert_eq!(health("strings").map(|h| h.len), Some(2));
+
+    drop(strings);
+    let report = registry::report();
+    assert!(report.iter().any(|(name, _)| name == "buffers"));
+    assert!(!report.iter().any(|(name, _)| name == "strings"));
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "stream")]
+
+use futures_core::Stream;
+use remem::{GrowthPolicy, ItemGuard, Pool, PoolStream};
+use std::future;
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::time;
+
+async fn next<'a, T>(stream: &mut PoolStream<'a, T>) -> ItemGuard<'a, T> {
+    future::poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx))
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn stream_waits_for_returns() {
+    let pool = Pool::builder(Vec::<u8>::new)
+        .growth_policy(GrowthPolicy::FailOnEmpty)
+        .build();
+    pool.prefill(3);
+    let mut stream = pool.stream();
+
+    let mut items = Vec::new();
+    for _ in 0..3 {
+        items.push(next(&mut stream).await);
+    }
+    assert!(pool.is_empty());
+    assert!(time::timeout(Duration::from_millis(10), next(&mut stream))
+        .await
+        .is_err());
+
+    items.pop();
+    let item = time::timeout(Duration::from_secs(1), next(&mut stream))
+        .await
+        .unwrap();
+    assert_eq!(pool.in_use(), 3);
+    drop(item);
+}
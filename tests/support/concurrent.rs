@@ -0,0 +1,109 @@
+//! Randomized concurrent `get`/drop sequences which check that no item is
+//! lost or handed out twice.
+//!
+//! Shared by the `concurrent` fuzz target and the replay test in
+//! `tests/fuzz_replay.rs`. Run the fuzz target under ThreadSanitizer with
+//! `cargo +nightly fuzz run concurrent --sanitizer thread` from `fuzz/`.
+
+use remem::{ItemGuard, Pool};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+const MAX_THREADS: usize = 4;
+
+struct Item {
+    id: usize,
+    writes: usize,
+}
+
+/// Run the operations encoded in `data`.
+///
+/// The first byte picks the number of threads and the remaining bytes are
+/// dealt out to them round-robin, each byte encoding one operation.
+pub fn run(data: &[u8]) {
+    let (first, ops) = match data.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+    let threads = *first as usize % MAX_THREADS + 1;
+
+    let created = Arc::new(AtomicUsize::new(0));
+    let cleared = Arc::new(AtomicUsize::new(0));
+    let pool = {
+        let created = created.clone();
+        let cleared = cleared.clone();
+        Pool::with_clear(
+            move || Item {
+                id: created.fetch_add(1, Ordering::SeqCst),
+                writes: 0,
+            },
+            move |item| {
+                item.writes = 0;
+                cleared.fetch_add(1, Ordering::SeqCst);
+            },
+        )
+    };
+    let checked_out = Arc::new(Mutex::new(HashSet::new()));
+    let returned = Arc::new(AtomicUsize::new(0));
+
+    let handles: Vec<_> = (0..threads)
+        .map(|n| {
+            let ops: Vec<u8> = ops.iter().skip(n).step_by(threads).copied().collect();
+            let pool = pool.clone();
+            let checked_out = checked_out.clone();
+            let returned = returned.clone();
+            thread::spawn(move || {
+                let mut held: Vec<ItemGuard<'_, Item>> = Vec::new();
+                let release = |item: ItemGuard<'_, Item>| {
+                    assert!(checked_out.lock().unwrap().remove(&item.id));
+                    returned.fetch_add(1, Ordering::SeqCst);
+                };
+                for op in ops {
+                    match op % 4 {
+                        0 | 1 => {
+                            let item = pool.get();
+                            assert_eq!(item.writes, 0, "item wasn't cleared");
+                            assert!(
+                                checked_out.lock().unwrap().insert(item.id),
+                                "item {} was handed out twice",
+                                item.id
+                            );
+                            held.push(item);
+                        }
+                        2 if !held.is_empty() => {
+                            let index = op as usize / 4 % held.len();
+                            release(held.swap_remove(index));
+                        }
+                        3 => {
+                            if let Some(item) = held.last_mut() {
+                                item.writes += 1;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                for item in held {
+                    release(item);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let created = created.load(Ordering::SeqCst);
+    assert_eq!(
+        cleared.load(Ordering::SeqCst),
+        returned.load(Ordering::SeqCst)
+    );
+    assert_eq!(pool.len(), created, "items were lost");
+
+    let items: Vec<_> = (0..created).map(|_| pool.get()).collect();
+    let ids: HashSet<_> = items.iter().map(|item| item.id).collect();
+    assert_eq!(ids.len(), created, "items were duplicated");
+    drop(items);
+    pool.assert_no_leaks();
+}
@@ -1,6 +1,15 @@
-use remem::Pool;
+use remem::{
+    AdaptivePolicy, Freshness, GrowthPolicy, MetaPool, Pool, PoolGroup, PoolHandle, PoolRing,
+    PooledAny, SeedError, SizeClassPool, StatsSink, TieredPool, TryPool,
+};
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
 #[test]
+#[allow(clippy::redundant_closure)]
 fn it_works() {
     let pool = Pool::<Vec<u8>>::new(|| Vec::new());
     let mut item = pool.get();
@@ -9,3 +18,1660 @@ fn it_works() {
     let mut item = pool.get();
     item.push(1);
 }
+
+#[test]
+fn always_allocate_on_empty() {
+    let pool = Pool::builder(|| vec![0u8; 4])
+        .growth_policy(GrowthPolicy::AlwaysAllocate)
+        .build();
+    let item = pool.try_get().unwrap();
+    assert_eq!(*item, vec![0u8; 4]);
+}
+
+#[test]
+fn fail_on_empty() {
+    let pool = Pool::builder(|| vec![0u8; 4])
+        .growth_policy(GrowthPolicy::FailOnEmpty)
+        .build();
+    assert!(pool.try_get().is_none());
+
+    pool.prefill(1);
+    let item = pool.try_get().unwrap();
+    assert!(pool.try_get().is_none());
+
+    drop(item);
+    assert!(pool.try_get().is_some());
+}
+
+#[test]
+fn block_on_empty() {
+    let pool = Pool::builder(Vec::<u8>::new)
+        .growth_policy(GrowthPolicy::Block)
+        .build();
+    pool.prefill(1);
+    let mut item = pool.get();
+    item.push(1);
+
+    let p = pool.clone();
+    let t = thread::spawn(move || p.get().clone());
+
+    thread::sleep(Duration::from_millis(50));
+    drop(item);
+    assert_eq!(t.join().unwrap(), vec![1]);
+}
+
+#[test]
+fn swap_item() {
+    let pool = Pool::new(|| vec![0u8; 4]);
+    let mut item = pool.get();
+    item.push(1);
+
+    let old = item.swap(vec![2]);
+    assert_eq!(old, vec![0, 0, 0, 0, 1]);
+    assert_eq!(*item, vec![2]);
+
+    drop(item);
+    assert_eq!(*pool.get(), vec![2]);
+}
+
+#[test]
+fn copy_fast_path() {
+    assert!(!Pool::is_beneficial_for::<u64>());
+    assert!(Pool::is_beneficial_for::<[u8; 4096]>());
+
+    let pool = Pool::new(|| 7u64);
+    let mut item = pool.get_copy();
+    assert_eq!(*item, 0);
+    *item = 8;
+    drop(item);
+    assert!(pool.is_empty());
+    assert_eq!(*pool.get_copy(), 0);
+}
+
+#[test]
+fn merge_pools() {
+    let a = Pool::new(Vec::<u8>::new);
+    let b = Pool::new(Vec::<u8>::new);
+    a.prefill(2);
+    b.prefill(3);
+
+    a.merge(&b);
+    assert_eq!(a.len(), 5);
+    assert!(b.is_empty());
+
+    a.merge(&a.clone());
+    assert_eq!(a.len(), 5);
+}
+
+#[test]
+fn count_reuses() {
+    let pool = Pool::counted_with_clear(Vec::<u8>::new, |v| v.clear());
+    for expected in 0..3 {
+        let (mut item, reuses) = pool.get_counted();
+        assert_eq!(reuses, expected);
+        assert!(item.is_empty());
+        item.push(1);
+    }
+    assert_eq!(pool.len(), 1);
+}
+
+#[test]
+fn change_capacity() {
+    let pool = Pool::builder(Vec::<u8>::new).capacity(1).build();
+    assert_eq!(pool.capacity(), 1);
+
+    let (a, b) = (pool.get(), pool.get());
+    drop(a);
+    drop(b);
+    assert_eq!(pool.len(), 1);
+
+    pool.set_capacity(3);
+    let (a, b, c) = (pool.get(), pool.get(), pool.get());
+    drop(a);
+    drop(b);
+    drop(c);
+    assert_eq!(pool.len(), 3);
+
+    pool.set_capacity(2);
+    assert_eq!(pool.len(), 3);
+    let a = pool.get();
+    drop(a);
+    assert_eq!(pool.len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "1 guard(s) were not returned to the pool")]
+fn detect_leaks() {
+    let pool = Pool::new(Vec::<u8>::new);
+    drop(pool.get());
+    pool.assert_no_leaks();
+
+    std::mem::forget(pool.get());
+    pool.assert_no_leaks();
+}
+
+#[test]
+#[cfg(feature = "leak_detection")]
+fn leak_locations() {
+    let pool = Pool::new(Vec::<u8>::new);
+    let line = line!() + 1;
+    let guard = pool.get();
+    let location = format!("{}:{}:", file!(), line);
+    assert!(format!("{:?}", guard).contains(&location));
+    std::mem::forget(guard);
+
+    let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| pool.assert_no_leaks()))
+        .unwrap_err();
+    let message = panic.downcast_ref::<String>().unwrap();
+    assert!(message.contains(&location), "{}", message);
+}
+
+#[test]
+fn recycle_batch() {
+    let cleared = Arc::new(AtomicUsize::new(0));
+    let c = cleared.clone();
+    let pool = Pool::with_clear(Vec::<u8>::new, move |v| {
+        v.clear();
+        c.fetch_add(1, Ordering::SeqCst);
+    });
+
+    pool.recycle_batch(vec![vec![1], vec![2], vec![3]]);
+    assert_eq!(pool.len(), 3);
+    assert_eq!(cleared.load(Ordering::SeqCst), 3);
+    assert!(pool.get().is_empty());
+}
+
+#[test]
+fn from_seed_items() {
+    let created = Arc::new(AtomicUsize::new(0));
+    let c = created.clone();
+    let create = move || {
+        c.fetch_add(1, Ordering::SeqCst);
+        vec![0u8]
+    };
+    let pool = Pool::from((create, vec![vec![1u8], vec![2u8]]));
+    assert_eq!(pool.len(), 2);
+
+    let (a, b) = (pool.get(), pool.get());
+    assert_eq!((a[0], b[0]), (1, 2));
+    assert_eq!(created.load(Ordering::SeqCst), 0);
+
+    assert_eq!(*pool.get(), vec![0]);
+    assert_eq!(created.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn split_buffer() {
+    let pool = Pool::new(|| vec![0u8; 8]);
+    let mut buf = pool.get();
+    let (front, back) = buf.split_at_mut(3);
+    assert_eq!((front.len(), back.len()), (3, 5));
+    front[0] = 1;
+    back[0] = 2;
+    assert_eq!(*buf, vec![1, 0, 0, 2, 0, 0, 0, 0]);
+}
+
+#[test]
+#[should_panic(expected = "split point 9 is out of bounds")]
+fn split_buffer_out_of_bounds() {
+    let pool = Pool::new(|| vec![0u8; 8]);
+    pool.get().split_at_mut(9);
+}
+
+#[test]
+fn spin_then_allocate() {
+    let created = Arc::new(AtomicUsize::new(0));
+    let c = created.clone();
+    // Only the checkout made while the pool is empty spins, until the other
+    // thread returns the item.
+    let pool = Pool::builder(move || {
+        c.fetch_add(1, Ordering::SeqCst);
+        vec![0u8]
+    })
+    .spin_tries(usize::MAX)
+    .build();
+    pool.prefill(1);
+    let mut item = pool.get();
+    item[0] = 1;
+    let spinning = Barrier::new(2);
+    thread::scope(|s| {
+        s.spawn(|| {
+            spinning.wait();
+            thread::sleep(Duration::from_millis(10));
+            drop(item);
+        });
+        spinning.wait();
+        assert_eq!(*pool.get(), vec![1]);
+    });
+    assert_eq!(created.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn read_only_guard() {
+    let cleared = Arc::new(AtomicUsize::new(0));
+    let c = cleared.clone();
+    let pool = Pool::with_clear(
+        || vec![1u8, 2, 3],
+        move |_| {
+            c.fetch_add(1, Ordering::SeqCst);
+        },
+    );
+
+    let item = pool.get_ref_uncleared();
+    assert_eq!(*item, vec![1, 2, 3]);
+    assert_eq!(item.len(), 3);
+    drop(item);
+
+    assert_eq!(pool.len(), 1);
+    assert_eq!(cleared.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+#[cfg(all(feature = "unstable", not(miri)))]
+fn raw_queue() {
+    let pool = Pool::new(Vec::<u8>::new);
+    pool.raw_queue().push(vec![1, 2, 3]);
+    assert_eq!(pool.len(), 1);
+    assert_eq!(*pool.get(), vec![1, 2, 3]);
+}
+
+#[test]
+fn wait_for_returned_item() {
+    let pool = Pool::new(|| vec![0u8]);
+    let mut item = pool.get();
+    item[0] = 1;
+
+    let p = pool.clone();
+    let t = thread::spawn(move || p.try_get_for(Duration::from_secs(10)).clone());
+
+    thread::sleep(Duration::from_millis(50));
+    drop(item);
+    assert_eq!(t.join().unwrap(), vec![1]);
+
+    let item = pool.try_get_for(Duration::from_millis(1));
+    assert_eq!(*item, vec![1]);
+    assert_eq!(*pool.try_get_for(Duration::from_millis(1)), vec![0]);
+}
+
+#[test]
+fn uninit_bytes() {
+    let pool = Pool::uninit_bytes(16);
+    let mut buf = pool.get();
+    assert_eq!(buf.len(), 16);
+    for (i, byte) in buf.iter_mut().enumerate() {
+        byte.write(i as u8);
+    }
+    // SAFETY: every byte was written above. Miri checks this: reading the
+    // buffer before the loop is reported as use of uninitialized memory.
+    let bytes = unsafe { buf.assume_init_mut() };
+    assert_eq!(bytes[15], 15);
+    bytes[0] = 42;
+    drop(buf);
+
+    // The buffer is reused as-is, so previously written bytes are still
+    // initialized.
+    let mut buf = pool.get();
+    assert_eq!(unsafe { buf.assume_init_mut() }[0], 42);
+}
+
+#[test]
+fn transform_all() {
+    let pool = Pool::new(|| vec![0u8; 2]);
+    pool.prefill(3);
+
+    pool.transform_all(|mut v| {
+        v.resize(8, 0);
+        v
+    });
+    assert_eq!(pool.len(), 3);
+
+    let items = [pool.get(), pool.get(), pool.get()];
+    assert!(items.iter().all(|v| v.len() == 8));
+    assert_eq!(pool.get().len(), 2);
+}
+
+#[test]
+fn get_sized() {
+    let pool = Pool::builder(Vec::<u8>::new)
+        .create_sized(Vec::with_capacity)
+        .grow(|v, hint| v.reserve(hint))
+        .build();
+
+    let item = pool.get_sized(64);
+    assert!(item.capacity() >= 64);
+    drop(item);
+
+    let item = pool.get_sized(1024);
+    assert!(item.capacity() >= 1024);
+    drop(item);
+    assert_eq!(pool.len(), 1);
+
+    assert_eq!(pool.get().capacity(), 1024);
+}
+
+#[test]
+fn object_pool_compat() {
+    use remem::compat::{PoolCompat, Reusable};
+
+    let pool = Pool::new(Vec::<u8>::new);
+    assert!(pool.try_pull().is_none());
+
+    let mut item: Reusable<'_, Vec<u8>> = pool.pull(|| vec![1]);
+    item.push(2);
+    drop(item);
+    assert_eq!(*pool.try_pull().unwrap(), vec![1, 2]);
+
+    pool.attach(vec![3]);
+    assert_eq!(pool.len(), 2);
+}
+
+#[test]
+fn take_and_refill() {
+    let pool = Pool::new(|| vec![0u8]);
+    let mut item = pool.get();
+    item[0] = 1;
+
+    assert_eq!(item.take_and_refill(), vec![1]);
+    assert_eq!(pool.len(), 1);
+    assert_eq!(*pool.get(), vec![0]);
+}
+
+#[test]
+fn peek_one() {
+    let pool = Pool::new(Vec::<u8>::new);
+    assert_eq!(pool.peek_one(), None);
+
+    pool.recycle_batch(vec![vec![1], vec![2]]);
+    assert_eq!(pool.peek_one(), Some(vec![1]));
+    assert_eq!(pool.len(), 2);
+    assert_eq!(pool.peek_one(), Some(vec![2]));
+}
+
+#[test]
+fn string_pool() {
+    let pool = Pool::string(16);
+    let mut s = pool.get();
+    s.push_str("hello, world! this is longer than sixteen bytes");
+    let capacity = s.capacity();
+    drop(s);
+
+    let s = pool.get();
+    assert!(s.is_empty());
+    assert_eq!(s.capacity(), capacity);
+}
+
+#[test]
+fn extend_and_write_through_guard() {
+    fn fill(buf: &mut impl Extend<u8>) {
+        buf.extend(vec![1, 2]);
+    }
+
+    let pool = Pool::new(Vec::<u8>::new);
+    let mut buf = pool.get();
+    fill(&mut buf);
+    write!(buf, "ab").unwrap();
+    assert_eq!(*buf, vec![1, 2, b'a', b'b']);
+}
+
+#[test]
+fn drain_filter() {
+    let pool = Pool::new(Vec::<u8>::new);
+    pool.recycle_batch(vec![
+        Vec::with_capacity(8),
+        Vec::with_capacity(4096),
+        Vec::with_capacity(16),
+        Vec::with_capacity(8192),
+    ]);
+
+    let evicted = pool.drain_filter(|v| v.capacity() > 1024);
+    assert_eq!(evicted.len(), 2);
+    assert!(evicted.iter().all(|v| v.capacity() > 1024));
+    assert_eq!(pool.len(), 2);
+    assert!(pool.get().capacity() <= 1024);
+}
+
+#[test]
+fn unsync_reuse_order() {
+    let pool = Pool::new_unsync(Vec::<u8>::new);
+    let mut a = pool.get();
+    let mut b = pool.get();
+    let mut c = pool.get();
+    a.push(1);
+    b.push(2);
+    c.push(3);
+
+    drop(b);
+    drop(a);
+    drop(c);
+    assert_eq!(pool.len(), 3);
+
+    assert_eq!(*pool.get(), vec![3]);
+    let (c, a) = (pool.get(), pool.get());
+    assert_eq!((c[0], a[0]), (3, 1));
+    assert_eq!(*pool.get(), vec![2]);
+}
+
+#[test]
+fn reborrow() {
+    fn fill(buf: &mut Vec<u8>) {
+        buf.push(1);
+    }
+
+    let pool = Pool::new(Vec::<u8>::new);
+    let mut buf = pool.get();
+    fill(buf.reborrow());
+    buf.push(2);
+    assert_eq!(*buf, vec![1, 2]);
+}
+
+#[test]
+fn prefault_buffers() {
+    let pool = Pool::builder(|| Vec::with_capacity(3 * 4096 + 1))
+        .prefault()
+        .build();
+    let buf = pool.get();
+    assert!(buf.is_empty());
+    assert!(buf.capacity() > 3 * 4096);
+
+    let pool = Pool::builder(|| vec![7u8; 5000]).prefault().build();
+    assert!(pool.get().iter().all(|&b| b == 7));
+}
+
+#[test]
+fn get_array() {
+    let pool = Pool::new(Vec::<u8>::new);
+    let [mut a, mut b, c, d] = pool.get_array();
+    a.push(1);
+    b.push(2);
+    assert!(c.is_empty() && d.is_empty());
+    drop((a, b, c, d));
+    assert_eq!(pool.len(), 4);
+}
+
+#[test]
+fn adaptive_capacity() {
+    let pool = Pool::builder(Vec::<u8>::new)
+        .adaptive(AdaptivePolicy::new(1.2, 16))
+        .build();
+
+    let burst: Vec<_> = (0..10).map(|_| pool.get()).collect();
+    assert_eq!(pool.in_use(), 10);
+    drop(burst);
+    assert_eq!(pool.len(), 10);
+
+    for _ in 0..40 {
+        drop(pool.get());
+    }
+    assert_eq!(pool.in_use(), 0);
+    assert_eq!(pool.len(), 2);
+}
+
+#[test]
+fn on_get_callback() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let e = events.clone();
+    let pool = Pool::builder(Vec::<u8>::new)
+        .on_get(move |hit| e.lock().unwrap().push(hit))
+        .build();
+
+    let a = pool.get();
+    let b = pool.get();
+    drop(a);
+    let c = pool.get();
+    drop((b, c));
+    drop(pool.get());
+
+    assert_eq!(*events.lock().unwrap(), vec![false, false, true, true]);
+}
+
+#[test]
+fn nested_bytes() {
+    let pool = Pool::nested_bytes(2, 16);
+    let mut rows = pool.get();
+    assert_eq!(rows.len(), 2);
+    rows[0].extend_from_slice(b"hello");
+    rows[1].extend_from_slice(b"world");
+    let ptrs: Vec<_> = rows.iter().map(|row| row.as_ptr()).collect();
+    drop(rows);
+
+    let rows = pool.get();
+    assert_eq!(rows.len(), 2);
+    assert!(rows
+        .iter()
+        .all(|row| row.is_empty() && row.capacity() >= 16));
+    assert_eq!(
+        rows.iter().map(|row| row.as_ptr()).collect::<Vec<_>>(),
+        ptrs
+    );
+}
+
+#[test]
+#[cfg(feature = "portable-atomic")]
+fn portable_atomic_counters() {
+    let pool = Pool::builder(Vec::<u8>::new).capacity(1).build();
+    let (a, b) = (pool.get(), pool.get());
+    assert_eq!(pool.in_use(), 2);
+    drop((a, b));
+    assert_eq!((pool.in_use(), pool.len()), (0, 1));
+}
+
+#[test]
+#[cfg(all(feature = "numa", target_os = "linux"))]
+fn numa_local_reuse() {
+    let pool = remem::NumaPool::new(Vec::<u8>::new);
+    assert!(pool.node_count() >= 1);
+    let node = pool.current_node();
+    drop(pool.get());
+    let stored: usize = (0..pool.node_count()).map(|i| pool.node(i).len()).sum();
+    assert_eq!(stored, 1);
+    assert!(node < pool.node_count());
+}
+
+#[test]
+#[cfg(feature = "numa")]
+fn numa_steal_from_other_node() {
+    let pool = remem::NumaPool::with_nodes(Vec::<u8>::new, 2);
+    let other = (pool.current_node() + 1) % 2;
+    pool.node(other).recycle_batch(vec![vec![1]]);
+
+    let item = pool.get();
+    assert_eq!(*item, vec![1]);
+    drop(item);
+    assert_eq!(pool.node(other).len(), 1);
+}
+
+#[test]
+fn try_map() {
+    fn header(buf: &mut [u8]) -> Result<u16, &'static str> {
+        match buf.get(..2) {
+            Some(&[a, b]) => Ok(u16::from_be_bytes([a, b])),
+            _ => Err("too short"),
+        }
+    }
+
+    let pool = Pool::new(Vec::<u8>::new);
+    let mut buf = pool.get();
+    buf.push(1);
+
+    let (mut buf, err) = buf.try_map(|b| header(b)).unwrap_err();
+    assert_eq!(err, "too short");
+    buf.push(2);
+
+    let mapped = buf.try_map(|b| header(b)).unwrap();
+    assert_eq!(*mapped, 0x0102);
+    assert_eq!(*mapped.item(), vec![1, 2]);
+    assert_eq!(pool.in_use(), 1);
+    drop(mapped);
+    assert_eq!(pool.len(), 1);
+}
+
+#[test]
+fn prefill_background() {
+    let pool = Pool::new(|| vec![0u8; 1024]);
+    let handle = pool.prefill_background(8);
+    drop(pool.get());
+    handle.join().unwrap();
+    assert!(pool.len() >= 8);
+}
+
+#[test]
+fn guards_iterator() {
+    let pool = Pool::new(Vec::<u8>::new);
+    let guards: Vec<_> = pool.guards().take(3).collect();
+    assert_eq!(guards.len(), 3);
+    assert_eq!(pool.in_use(), 3);
+    drop(guards);
+    assert_eq!(pool.len(), 3);
+
+    for (mut buf, byte) in pool.guards().zip(0..2) {
+        buf.push(byte);
+    }
+    assert_eq!(pool.len(), 3);
+}
+
+#[test]
+fn from_template() {
+    let pool = Pool::from_template(vec![1u8, 2, 3]);
+    let (mut a, b) = (pool.get(), pool.get());
+    assert_eq!(*a, vec![1, 2, 3]);
+    assert_eq!(*b, vec![1, 2, 3]);
+    a.push(4);
+    assert_eq!(*b, vec![1, 2, 3]);
+}
+
+#[test]
+fn shrink_to_on_return() {
+    let pool = Pool::builder(Vec::new)
+        .clear(Vec::clear)
+        .shrink_to_on_return(64)
+        .build();
+    let mut buf = pool.get();
+    buf.extend_from_slice(&[0; 4096]);
+    drop(buf);
+
+    let buf = pool.get();
+    assert!(buf.capacity() <= 64);
+}
+
+// Also run under Miri in CI, where the pool uses a locked queue.
+#[test]
+fn share_between_threads() {
+    let pool = Pool::with_clear(Vec::<u8>::new, Vec::clear);
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let pool = pool.clone();
+            thread::spawn(move || {
+                for _ in 0..10 {
+                    let mut buf = pool.get();
+                    buf.push(i);
+                    assert_eq!(*buf, vec![i]);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert!(!pool.is_empty() && pool.len() <= 4);
+    pool.assert_no_leaks();
+}
+
+#[test]
+fn try_lock_clear_shared() {
+    let pool = Pool::shared(Vec::<u8>::new, Vec::clear);
+    let guard = pool.get();
+    guard.lock().unwrap().push(1);
+    drop(guard);
+    assert!(pool.get().lock().unwrap().is_empty());
+
+    // Returning the item while its lock is held replaces it instead of
+    // deadlocking or handing out the uncleared value.
+    let guard = pool.get();
+    let shared = Arc::clone(&guard);
+    let mut value = shared.lock().unwrap();
+    value.push(2);
+    drop(guard);
+    drop(value);
+    assert!(pool.get().lock().unwrap().is_empty());
+    assert_eq!(*shared.lock().unwrap(), vec![2]);
+
+    // A poisoned lock is cleared and unpoisoned.
+    let guard = pool.get();
+    let shared = Arc::clone(&guard);
+    let _ = std::thread::spawn(move || {
+        let mut value = shared.lock().unwrap();
+        value.push(3);
+        panic!("poison the lock");
+    })
+    .join();
+    assert!(guard.is_poisoned());
+    drop(guard);
+    let guard = pool.get();
+    assert!(!guard.is_poisoned());
+    assert!(guard.lock().unwrap().is_empty());
+}
+
+#[test]
+fn collect_stats_into_sink() {
+    #[derive(Default)]
+    struct Counts {
+        hits: AtomicUsize,
+        misses: AtomicUsize,
+        returns: AtomicUsize,
+    }
+
+    impl StatsSink for Counts {
+        fn record_get(&self, hit: bool) {
+            let count = if hit { &self.hits } else { &self.misses };
+            count.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn record_return(&self) {
+            self.returns.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let counts = Arc::new(Counts::default());
+    let pool = Pool::builder(Vec::<u8>::new)
+        .collect_stats_into(counts.clone())
+        .build();
+    let (a, b) = (pool.get(), pool.get());
+    drop((a, b));
+    for _ in 0..3 {
+        drop(pool.get());
+    }
+    drop(pool.get().take_and_refill());
+
+    assert_eq!(counts.hits.load(Ordering::SeqCst), 4);
+    assert_eq!(counts.misses.load(Ordering::SeqCst), 2);
+    assert_eq!(counts.returns.load(Ordering::SeqCst), 5);
+}
+
+#[test]
+fn get_zeroed() {
+    let pool = Pool::new(|| vec![0u8; 4]);
+    pool.get().copy_from_slice(&[1, 2, 3, 4]);
+    assert_eq!(*pool.get(), [1, 2, 3, 4]);
+    assert_eq!(*pool.get_zeroed(), [0; 4]);
+    assert_eq!(*pool.get_zeroed(), [0; 4]);
+}
+
+#[test]
+fn with_item() {
+    let pool = Pool::with_clear(Vec::<u8>::new, Vec::clear);
+    let sum = pool.get().with(|buf| {
+        buf.extend_from_slice(&[1, 2, 3]);
+        buf.iter().map(|&b| u32::from(b)).sum::<u32>()
+    });
+    assert_eq!(sum, 6);
+    assert_eq!(pool.len(), 1);
+    assert!(pool.get().is_empty());
+}
+
+#[test]
+fn alloc_budget() {
+    let exceeded = Arc::new(Mutex::new(Vec::new()));
+    let e = exceeded.clone();
+    let pool = Pool::builder(Vec::<u8>::new)
+        .alloc_budget(2, move |allocations| e.lock().unwrap().push(allocations))
+        .build();
+    let (a, b) = (pool.get(), pool.get());
+    assert!(exceeded.lock().unwrap().is_empty());
+    let c = pool.get();
+    assert_eq!(*exceeded.lock().unwrap(), [3]);
+    drop((a, b, c));
+
+    // Reused items don't count against the budget.
+    drop(pool.get_array::<3>());
+    assert_eq!(*exceeded.lock().unwrap(), [3]);
+
+    assert_eq!(pool.reset_alloc_budget(), 3);
+    drop(pool.get_array::<5>());
+    assert_eq!(*exceeded.lock().unwrap(), [3]);
+    drop(pool.get_array::<6>());
+    assert_eq!(*exceeded.lock().unwrap(), [3, 3]);
+}
+
+#[test]
+fn return_through_handle() {
+    struct Message {
+        body: Option<Vec<u8>>,
+        pool: PoolHandle<Vec<u8>>,
+    }
+
+    impl Drop for Message {
+        fn drop(&mut self) {
+            if let Some(body) = self.body.take() {
+                self.pool.return_item(body);
+            }
+        }
+    }
+
+    let pool = Pool::with_clear(Vec::<u8>::new, Vec::clear);
+    let message = Message {
+        body: Some(pool.get().take_and_refill()),
+        pool: pool.handle(),
+    };
+    assert_eq!(pool.len(), 1);
+    drop(message);
+    assert_eq!(pool.len(), 2);
+}
+
+#[test]
+fn get_waiting() {
+    let pool = Pool::new(Vec::<u8>::new);
+    let (first, reused) = pool.get_waiting(Duration::from_millis(1));
+    assert!(!reused);
+    let (_second, reused) = pool.get_waiting(Duration::from_millis(1));
+    assert!(!reused);
+    drop(first);
+    let (_first, reused) = pool.get_waiting(Duration::from_millis(1));
+    assert!(reused);
+}
+
+#[test]
+fn size_classes() {
+    let pool = SizeClassPool::new(&[16 * 1024, 1024, 4096, 1024]);
+    assert_eq!(pool.sizes().collect::<Vec<_>>(), [1024, 4096, 16 * 1024]);
+
+    for (size, class) in [(1, 1024), (1024, 1024), (1025, 4096), (10_000, 16 * 1024)] {
+        let mut buf = pool.get(size);
+        assert!(buf.capacity() >= class && buf.capacity() < class * 2);
+        buf.push(1);
+        drop(buf);
+        let class = pool.class(class).unwrap();
+        assert_eq!(class.len(), 1);
+        assert!(class.get().is_empty());
+    }
+
+    assert!(pool.class(20_000).is_none());
+    let buf = pool.get(20_000);
+    assert!(buf.capacity() >= 20_000);
+    drop(buf);
+    assert_eq!(
+        pool.sizes()
+            .map(|size| pool.class(size).unwrap().len())
+            .sum::<usize>(),
+        3
+    );
+}
+
+#[test]
+fn get_tagged() {
+    let pool = Pool::new(Vec::<u8>::new);
+    let (_item, first) = pool.get_tagged();
+    let mut previous = first;
+    for _ in 0..5 {
+        let (item, tag) = pool.get_tagged();
+        assert!(tag > previous);
+        previous = tag;
+        drop(item);
+    }
+    assert_eq!(previous, first + 5);
+}
+
+#[test]
+fn finalize_on_pool_drop() {
+    let finalized = Arc::new(AtomicUsize::new(0));
+    let f = finalized.clone();
+    let pool = Pool::builder(Vec::<u8>::new)
+        .on_pool_drop(move |_| {
+            f.fetch_add(1, Ordering::SeqCst);
+        })
+        .build();
+    pool.prefill(3);
+    let handle = pool.clone();
+    drop(pool);
+    assert_eq!(finalized.load(Ordering::SeqCst), 0);
+    drop(handle);
+    assert_eq!(finalized.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn pinned_items_stay_put() {
+    let pool = Pool::pinned_with_clear(|| [0u8; 64], |buf| buf.get_mut().fill(0));
+    let mut item = pool.get_pinned();
+    let address = &*item as *const [u8; 64];
+    item.as_mut().get_mut()[0] = 1;
+    assert_eq!(&*item as *const _, address);
+    drop(item);
+
+    let item = pool.get_pinned();
+    assert_eq!(&*item as *const _, address);
+    assert_eq!(item[0], 0);
+}
+
+#[test]
+fn tiered_pool() {
+    let pool = TieredPool::with_clear(Vec::<u8>::new, Vec::clear, 1, 2);
+    let items: Vec<_> = (0..4).map(|_| pool.get()).collect();
+    let tiers = || (pool.l1().len(), pool.l2().len());
+
+    let mut items = items.into_iter();
+    drop(items.next());
+    assert_eq!(tiers(), (1, 0));
+    drop(items.next());
+    assert_eq!(tiers(), (1, 1));
+    drop(items.next());
+    assert_eq!(tiers(), (1, 2));
+    drop(items.next());
+    assert_eq!(tiers(), (1, 2));
+
+    let (a, b) = (pool.get(), pool.get());
+    assert_eq!(tiers(), (0, 1));
+    drop((a, b));
+    assert_eq!(tiers(), (1, 2));
+}
+
+#[test]
+fn buffer_usage() {
+    let pool = Pool::new(|| Vec::with_capacity(64));
+    let mut buf = pool.get();
+    assert_eq!(buf.usage(), (0, 64));
+    buf.write_all(b"hello").unwrap();
+    assert_eq!(buf.usage(), (5, 64));
+}
+
+#[test]
+fn clear_owned_replaces_items() {
+    let pool = Pool::builder(|| Vec::<u8>::with_capacity(16))
+        .clear(Vec::clear)
+        .clear_owned(|buf| {
+            if buf.capacity() > 1024 {
+                Vec::with_capacity(16)
+            } else {
+                buf
+            }
+        })
+        .build();
+    pool.get().extend_from_slice(&[1; 8]);
+    assert_eq!(pool.get().capacity(), 16);
+
+    pool.get().extend_from_slice(&[1; 4096]);
+    let buf = pool.get();
+    assert!(buf.is_empty());
+    assert_eq!(buf.capacity(), 16);
+}
+
+#[test]
+fn drain_with_wait() {
+    let pool = Pool::new(|| vec![0u8]);
+    pool.prefill(2);
+    let mut item = pool.get();
+    item[0] = 1;
+    let waiter = {
+        let pool = pool.clone();
+        thread::spawn(move || pool.drain_with_wait(Duration::from_secs(10)))
+    };
+    thread::sleep(Duration::from_millis(50));
+    drop(item);
+
+    let mut items = waiter.join().unwrap();
+    items.sort();
+    assert_eq!(items, [vec![0], vec![1]]);
+    assert!(pool.is_empty());
+}
+
+#[test]
+fn try_pool() {
+    let fail = Arc::new(AtomicUsize::new(0));
+    let f = fail.clone();
+    let pool = TryPool::with_clear(
+        move || match f.load(Ordering::SeqCst) {
+            0 => Ok(Vec::<u8>::with_capacity(8)),
+            _ => Err("out of buffers"),
+        },
+        Vec::clear,
+    );
+    let mut buf = pool.get().unwrap();
+    buf.push(1);
+    drop(buf);
+
+    fail.store(1, Ordering::SeqCst);
+    let buf = pool.get().unwrap();
+    assert!(buf.is_empty());
+    assert_eq!(pool.get().unwrap_err(), "out of buffers");
+    drop(buf);
+    assert_eq!(pool.len(), 1);
+}
+
+#[test]
+fn size_histogram() {
+    let pool = Pool::new(Vec::new);
+    let small = [pool.get_with_capacity(100), pool.get_with_capacity(128)];
+    let large = pool.get_with_capacity(3000);
+    assert!(large.capacity() >= 3000);
+    drop(large);
+    // Reused, so it isn't recorded.
+    assert!(pool.get_with_capacity(5000).capacity() >= 5000);
+    drop(small);
+    assert_eq!(pool.size_histogram(), [(128, 2), (4096, 1)]);
+}
+
+#[test]
+fn replace_clear() {
+    let pool = Pool::new(|| vec![0u8; 4]);
+    let (mut a, mut b) = (pool.get(), pool.get());
+    a.fill(1);
+    b.fill(2);
+    drop(a);
+    assert_eq!(*pool.get(), [1; 4]);
+
+    pool.replace_clear(|buf: &mut Vec<u8>| buf.fill(0));
+    drop(b);
+    let (a, b) = (pool.get(), pool.get());
+    assert_eq!(*a, [1; 4]);
+    assert_eq!(*b, [0; 4]);
+}
+
+#[test]
+fn pool_group_stealing() {
+    let group = PoolGroup::new(Vec::<u8>::new, 2);
+    group.pool(0).prefill(2);
+
+    let stolen = group.get_stealing(1);
+    assert_eq!((group.pool(0).len(), group.pool(1).len()), (1, 0));
+    drop(stolen);
+    assert_eq!((group.pool(0).len(), group.pool(1).len()), (1, 1));
+
+    let _local = group.get_stealing(1);
+    let _stolen = group.get_stealing(1);
+    let _created = group.get_stealing(1);
+    assert_eq!((group.pool(0).len(), group.pool(1).len()), (0, 0));
+    assert_eq!(group.pool(1).in_use(), 3);
+}
+
+#[test]
+fn pooled_any() {
+    let bytes = Pool::new(|| vec![1u8]);
+    let strings = Pool::string(8);
+    let mut items: Vec<PooledAny<'_>> = vec![bytes.get().into_any(), strings.get().into_any()];
+    assert_eq!(items[0].downcast_ref::<Vec<u8>>(), Some(&vec![1]));
+    assert!(items[0].downcast_ref::<String>().is_none());
+    items[1].downcast_mut::<String>().unwrap().push_str("hi");
+    assert!(items[1].is::<String>());
+
+    drop(items);
+    assert_eq!(bytes.len(), 1);
+    assert_eq!(strings.len(), 1);
+    assert!(strings.get().is_empty());
+}
+
+#[test]
+fn try_get_bounded() {
+    let pool = Pool::new(Vec::<u8>::new);
+    let a = pool.try_get_bounded(2).unwrap();
+    let _b = pool.try_get_bounded(2).unwrap();
+    assert!(pool.try_get_bounded(2).is_none());
+    drop(a);
+    assert!(pool.try_get_bounded(2).is_some());
+    assert_eq!(pool.in_use(), 1);
+}
+
+#[test]
+fn metadata_survives_clear() {
+    let pool: MetaPool<u32, Vec<u8>> = Pool::with_meta(Vec::new, Vec::clear);
+    let mut item = pool.get();
+    assert_eq!(*item.meta(), 0);
+    let (generation, buf) = item.parts_mut();
+    *generation += 1;
+    buf.push(1);
+    drop(item);
+
+    let mut item = pool.get();
+    assert_eq!(*item.meta(), 1);
+    assert!(item.is_empty());
+    *item.meta_mut() += 1;
+    drop(item);
+    assert_eq!(*pool.get().meta(), 2);
+}
+
+#[test]
+fn get_checked() {
+    let pool = Pool::new(Vec::<u8>::new);
+    let (item, freshness) = pool.get_checked();
+    assert_eq!(freshness, Freshness::Fresh);
+    drop(item);
+    assert_eq!(pool.get_checked().1, Freshness::Reused);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn max_hold() {
+    let warnings = Arc::new(Mutex::new(Vec::new()));
+    let w = warnings.clone();
+    let pool = Pool::builder(Vec::<u8>::new)
+        .max_hold(Duration::from_millis(5), move |held| {
+            w.lock().unwrap().push(held)
+        })
+        .build();
+    drop(pool.get());
+    assert!(warnings.lock().unwrap().is_empty());
+
+    let item = pool.get();
+    thread::sleep(Duration::from_millis(20));
+    drop(item);
+    let warnings = warnings.lock().unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0] >= Duration::from_millis(20) && warnings[0] < Duration::from_secs(10));
+}
+
+#[test]
+fn new_like_is_independent() {
+    let pool = Pool::builder(|| vec![0u8; 2])
+        .clear(|buf: &mut Vec<u8>| buf.fill(0))
+        .capacity(4)
+        .build();
+    pool.prefill(2);
+    let other = pool.new_like();
+    assert!(other.is_empty());
+    assert_eq!(other.capacity(), 4);
+
+    let mut item = other.get();
+    item[0] = 1;
+    drop(item);
+    assert_eq!((pool.len(), other.len()), (2, 1));
+    assert_eq!(*other.get(), [0, 0]);
+}
+
+#[test]
+fn dedup_returned_items() {
+    let pool = Pool::with_dedup(String::new, |_| {}, 8);
+    let (mut a, mut b, mut c) = (pool.get(), pool.get(), pool.get());
+    a.push_str("interned");
+    b.push_str("interned");
+    c.push_str("other");
+    drop((a, b, c));
+    assert_eq!(pool.len(), 2);
+
+    let mut items: Vec<_> = pool
+        .get_array::<2>()
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    items.sort();
+    assert_eq!(items, ["interned", "other"]);
+}
+
+#[test]
+fn reset_to_default() {
+    let pool = Pool::<(u32, String)>::reset_to_default();
+    let mut item = pool.get();
+    item.0 = 7;
+    item.1.push_str("dirty");
+    drop(item);
+    assert_eq!(pool.len(), 1);
+    assert_eq!(*pool.get(), (0, String::new()));
+}
+
+#[test]
+fn replace_with() {
+    let pool = Pool::new(|| vec![1u8, 2]);
+    let mut item = pool.get();
+    let old = item.replace_with(|old| old.iter().map(|b| b * 10).collect());
+    assert_eq!(old, [1, 2]);
+    assert_eq!(*item, [10, 20]);
+    drop(item);
+    assert_eq!(*pool.get(), [10, 20]);
+}
+
+#[test]
+fn buffer_chunks() {
+    let pool = Pool::new(|| vec![0u8; 12]);
+    let mut buf = pool.get();
+    let chunks: Vec<&mut [u8]> = buf.chunks_mut_exact(4).collect();
+    assert_eq!(
+        chunks.iter().map(|c| c.len()).collect::<Vec<_>>(),
+        [4, 4, 4]
+    );
+    for (i, chunk) in buf.chunks_mut_exact(4).enumerate() {
+        chunk[0] = i as u8 + 1;
+    }
+    assert_eq!(*buf, vec![1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0]);
+}
+
+#[test]
+#[should_panic(expected = "can't be split into chunks of 5 bytes")]
+fn buffer_chunks_uneven() {
+    let pool = Pool::new(|| vec![0u8; 12]);
+    pool.get().chunks_mut_exact(5).count();
+}
+
+#[test]
+fn warm_on_first_get() {
+    let pool = Pool::builder(|| vec![0u8]).warm_on_first_get(3).build();
+    assert_eq!(pool.len(), 0);
+    let item = pool.get();
+    assert_eq!(pool.len(), 3);
+    drop(item);
+    assert_eq!(pool.len(), 4);
+    let _item = pool.get();
+    assert_eq!(pool.len(), 3);
+}
+
+#[test]
+fn seed_bounded() {
+    let pool = Pool::try_from_bounded(|| 0, 3, vec![1, 2, 3]).unwrap();
+    assert_eq!(pool.len(), 3);
+    assert_eq!(
+        Pool::try_from_bounded(|| 0, 3, vec![1, 2, 3, 4]).unwrap_err(),
+        SeedError::TooManyItems { capacity: 3 }
+    );
+}
+
+#[test]
+fn size_priority() {
+    let pool = Pool::with_size_priority(Vec::<u8>::new, Vec::capacity);
+    for capacity in [1, 3, 2] {
+        pool.recycle_batch(vec![Vec::with_capacity(capacity)]);
+    }
+    let (first, second) = (pool.get(), pool.get());
+    assert_eq!((first.capacity(), second.capacity()), (3, 2));
+}
+
+#[test]
+fn using() {
+    let pool = Pool::with_clear(Vec::new, Vec::clear);
+    let len = pool.using(|buf| {
+        buf.push(1);
+        buf.len()
+    });
+    assert_eq!(len, 1);
+    assert_eq!(pool.len(), 1);
+    assert!(pool.get().is_empty());
+}
+
+#[test]
+fn fnmut_create() {
+    let mut next = 0;
+    let pool = Pool::with_fnmut_create(move || {
+        next += 1;
+        next
+    });
+    let (a, b, c) = (pool.get(), pool.get(), pool.get());
+    assert_eq!((*a, *b, *c), (1, 2, 3));
+    drop(a);
+    let (a, d) = (pool.get(), pool.get());
+    assert_eq!((*a, *d), (1, 4));
+}
+
+#[test]
+fn routed_return() {
+    let small = Pool::with_clear(|| Vec::<u8>::with_capacity(16), Vec::clear);
+    let large = Pool::with_clear(|| Vec::<u8>::with_capacity(16), Vec::clear);
+    let route = |buf: &Vec<u8>| if buf.capacity() <= 16 { &small } else { &large };
+
+    let (mut a, mut b) = (small.get_routed(route), small.get_routed(route));
+    a.push(1);
+    b.extend_from_slice(&[0; 64]);
+    drop((a, b));
+    assert_eq!((small.len(), large.len()), (1, 1));
+    assert!(large.get().capacity() >= 64);
+    assert_eq!(small.in_use(), 0);
+}
+
+#[test]
+fn pooled_bytes() {
+    let pool = Pool::builder(Vec::new).track_pooled_bytes().build();
+    pool.recycle_batch(vec![Vec::with_capacity(8), Vec::with_capacity(24)]);
+    assert_eq!(pool.pooled_bytes(), 32);
+    let mut item = pool.get();
+    assert_eq!(pool.pooled_bytes(), 24);
+    item.reserve_exact(16);
+    drop(item);
+    assert_eq!(pool.pooled_bytes(), 24 + 16);
+    assert_eq!(Pool::new(Vec::new).pooled_bytes(), 0);
+}
+
+#[test]
+fn warmup_wait() {
+    let created = Arc::new(AtomicUsize::new(0));
+    let c = created.clone();
+    let pool = Pool::builder(move || {
+        c.fetch_add(1, Ordering::SeqCst);
+        Vec::<u8>::new()
+    })
+    .warmup_wait(Duration::from_millis(500))
+    .build();
+    let held = pool.get();
+
+    // Every thread keeps its item until all of them have one, so the held item
+    // can only be reused once. The others wait for a return which never
+    // comes, then allocate once the wait times out.
+    let all = Barrier::new(4);
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                let _item = pool.get();
+                all.wait();
+            });
+        }
+        drop(held);
+    });
+    assert_eq!(pool.in_use(), 0);
+    assert!(created.load(Ordering::SeqCst) <= 4);
+
+    // Nothing is checked out which could be returned, so there's no wait.
+    let pool = Pool::builder(Vec::<u8>::new)
+        .warmup_wait(Duration::from_secs(60))
+        .build();
+    drop(pool.get());
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "the item was already written to")]
+fn write_once() {
+    let pool = Pool::new(Vec::new);
+    let mut item = pool.get_write_once();
+    item.write().push(1);
+    assert_eq!(*item, [1]);
+    assert_eq!(item.len(), 1);
+    item.write().push(2);
+}
+
+#[test]
+fn buffer_io_slices() {
+    let pool = Pool::new(|| b"hello world".to_vec());
+    let buf = pool.get();
+    let slices = buf.io_slices(&[6]);
+    assert_eq!(slices.len(), 2);
+    assert_eq!((&*slices[0], &*slices[1]), (&b"hello "[..], &b"world"[..]));
+
+    let mut out = Vec::new();
+    assert_eq!(out.write_vectored(&slices).unwrap(), 11);
+    assert_eq!(out, b"hello world");
+}
+
+#[test]
+#[should_panic(expected = "offset 2 is out of order")]
+fn buffer_io_slices_out_of_order() {
+    let pool = Pool::new(|| vec![0u8; 8]);
+    pool.get().io_slices(&[4, 2]);
+}
+
+#[test]
+fn pool_ring() {
+    let ring = PoolRing::<Vec<u8>, 3>::new(Vec::new);
+    let in_use = || (0..3).map(|i| ring.pool(i).in_use()).collect::<Vec<_>>();
+    let mut items = Vec::new();
+    for expected in [[1, 0, 0], [1, 1, 0], [1, 1, 1], [2, 1, 1]] {
+        items.push(ring.get());
+        assert_eq!(in_use(), expected);
+    }
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "was issued while already checked out")]
+fn double_issue() {
+    let pool = Pool::builder(|| Arc::new(0u8))
+        .check_double_issue(|item| Arc::as_ptr(item) as usize)
+        .build();
+    let item = pool.get();
+    let _other = pool.get();
+    pool.recycle_batch(vec![Arc::clone(&item)]);
+    let _again = pool.get();
+}
+
+#[test]
+fn pool_group_borrow() {
+    let group = PoolGroup::new(Vec::<u8>::new, 3);
+    group.pool(2).prefill(1);
+
+    let borrowed = group.borrow_stealing(0);
+    assert_eq!(group.pool(2).in_use(), 1);
+    drop(borrowed);
+    assert_eq!(
+        (0..3).map(|i| group.pool(i).len()).collect::<Vec<_>>(),
+        [0, 0, 1]
+    );
+
+    drop(group.borrow_stealing(1));
+    drop(group.borrow_stealing(1));
+    assert_eq!(
+        (0..3).map(|i| group.pool(i).len()).collect::<Vec<_>>(),
+        [0, 0, 1]
+    );
+}
+
+#[test]
+fn reserve_average() {
+    let pool = Pool::builder(Vec::new)
+        .clear(|buf: &mut Vec<u8>| {
+            buf.clear();
+            buf.shrink_to_fit();
+        })
+        .reserve_average()
+        .build();
+    pool.recycle_batch(vec![vec![0; 1000]; 40]);
+    let capacity = pool.get().capacity();
+    assert!((900..=1000).contains(&capacity), "{}", capacity);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn poison_on_return() {
+    let pool = Pool::builder(|| Vec::with_capacity(16))
+        .clear(Vec::clear)
+        .poison_on_return()
+        .build();
+    pool.get().extend_from_slice(b"secret");
+
+    let mut buf = pool.get();
+    assert!(buf.is_empty());
+    let capacity = buf.capacity();
+    // The whole allocation was written to by the poisoning.
+    unsafe { buf.set_len(capacity) };
+    assert!(buf.iter().all(|&b| b == 0xDB));
+    buf.clear();
+    buf.extend_from_slice(b"fresh");
+    assert_eq!(&buf[..], b"fresh");
+}
+
+#[test]
+fn shared_arc() {
+    let pool = Pool::with_clear(|| vec![1u8, 2, 3], Vec::clear);
+    let item = pool.get_arc();
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let item = Arc::clone(&item);
+            thread::spawn(move || item.iter().map(|&b| b as usize).sum::<usize>())
+        })
+        .collect();
+    drop(item);
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), 6);
+    }
+    assert_eq!(pool.len(), 1);
+    assert_eq!(pool.in_use(), 0);
+    assert!(pool.get().is_empty());
+}
+
+#[test]
+fn get_with_alloc() {
+    let pool = Pool::new(|| vec![0u8; 4]);
+    let item = pool.get_with_alloc(|| vec![7u8; 64]);
+    assert_eq!(*item, vec![7u8; 64]);
+    drop(item);
+    assert_eq!(pool.get_with_alloc(|| unreachable!()).len(), 64);
+}
+
+#[test]
+fn batched_return() {
+    let pool = Pool::batched_return(Vec::<u8>::new, 3);
+    let (a, b, c) = (pool.get(), pool.get(), pool.get());
+    drop((a, b));
+    assert_eq!(pool.len(), 0);
+    drop(c);
+    assert_eq!(pool.len(), 3);
+
+    drop(pool.get());
+    assert_eq!(pool.len(), 2);
+    pool.flush_returns();
+    assert_eq!(pool.len(), 3);
+
+    // Staged items are flushed when the thread exits.
+    let p = pool.clone();
+    thread::spawn(move || drop(p.get())).join().unwrap();
+    assert_eq!(pool.len(), 3);
+}
+
+#[test]
+fn dyn_write() {
+    fn log(out: &mut dyn Write, message: &str) {
+        writeln!(out, "[info] {}", message).unwrap();
+    }
+
+    let pool = Pool::with_clear(Vec::<u8>::new, Vec::clear);
+    let mut buf = pool.get();
+    log(buf.as_dyn_write(), "hello");
+    log(&mut *buf, "world");
+    assert_eq!(&buf[..], b"[info] hello\n[info] world\n");
+}
+
+#[test]
+fn flush_returns_before_staging() {
+    let pool = Pool::batched_return(Vec::<u8>::new, 2);
+    let p = pool.clone();
+    thread::spawn(move || {
+        p.flush_returns();
+        drop(p.get());
+        assert_eq!(p.len(), 0);
+        drop(p.get_array::<2>());
+        assert_eq!(p.len(), 2);
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn drain_with_wait_gets_every_returned_item() {
+    let pool = Pool::new(Vec::<u8>::new);
+    let start = Instant::now();
+    let drained = thread::scope(|s| {
+        let guards: Vec<_> = (0..4).map(|_| pool.get()).collect();
+        let waiter = s.spawn(|| pool.drain_with_wait(Duration::from_secs(10)));
+        for guard in guards {
+            s.spawn(move || drop(guard));
+        }
+        waiter.join().unwrap()
+    });
+    assert_eq!(drained.len(), 4);
+    assert!(pool.is_empty());
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn read_only_guard_clears_interior_mutability() {
+    let pool = Pool::with_clear(|| std::cell::Cell::new(0), |cell| cell.set(0));
+    let item = pool.get_ref();
+    item.set(1);
+    drop(item);
+    assert_eq!(pool.get().get(), 0);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn double_issue_isnt_counted() {
+    let pool = Pool::builder(|| Arc::new(0u8))
+        .check_double_issue(|item| Arc::as_ptr(item) as usize)
+        .build();
+    let item = pool.get();
+    pool.recycle_batch(vec![Arc::clone(&item)]);
+    let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| pool.get()));
+    assert!(panic.is_err());
+    assert_eq!(pool.in_use(), 1);
+    drop(item);
+    pool.assert_no_leaks();
+}
+
+#[test]
+fn try_get_bounded_counts_once() {
+    let creating = Arc::new(Barrier::new(2));
+    let created = Arc::new(Barrier::new(2));
+    let (c1, c2) = (creating.clone(), created.clone());
+    let pool = Pool::new(move || {
+        c1.wait();
+        c2.wait();
+        Vec::<u8>::new()
+    });
+    thread::scope(|s| {
+        let guard = s.spawn(|| pool.try_get_bounded(2).is_some());
+        creating.wait();
+        // The checkout in progress is counted once, so there's room for
+        // another.
+        assert_eq!(pool.in_use(), 1);
+        created.wait();
+        assert!(guard.join().unwrap());
+    });
+    assert_eq!(pool.in_use(), 0);
+
+    let pool = Pool::builder(Vec::<u8>::new)
+        .growth_policy(GrowthPolicy::FailOnEmpty)
+        .build();
+    assert!(pool.try_get_bounded(1).is_none());
+    assert_eq!(pool.in_use(), 0);
+}
+
+#[test]
+fn flush_current_thread_cache() {
+    let pool = Pool::batched_return(Vec::<u8>::new, 4);
+    drop(pool.get_array::<2>());
+    assert_eq!(pool.len(), 0);
+    pool.flush_current_thread_cache();
+    assert_eq!(pool.len(), 2);
+}
+
+#[test]
+fn peek_one_over_capacity() {
+    let pool = Pool::new(|| vec![1u8]);
+    pool.prefill(2);
+    pool.set_capacity(1);
+    assert_eq!(pool.peek_one(), Some(vec![1]));
+    assert_eq!(pool.len(), 2);
+}
+
+#[test]
+fn get_zeroed_fresh() {
+    let pool = Pool::new(|| vec![7u8; 4]);
+    assert_eq!(*pool.get_zeroed(), [0; 4]);
+}
+
+#[test]
+fn copy_fast_path_isnt_a_miss() {
+    let gets = Arc::new(AtomicUsize::new(0));
+    let g = gets.clone();
+    let pool = Pool::builder(|| 7u64)
+        .on_get(move |_| {
+            g.fetch_add(1, Ordering::SeqCst);
+        })
+        .alloc_budget(0, |_| panic!("the fast path spent the budget"))
+        .build();
+    let item = pool.get_copy();
+    assert_eq!(pool.in_use(), 1);
+    drop(item);
+    assert_eq!(gets.load(Ordering::SeqCst), 0);
+    pool.assert_no_leaks();
+}
+
+#[test]
+fn size_histogram_zero() {
+    let pool = Pool::new(Vec::<u8>::new);
+    let empty = pool.get_with_capacity(0);
+    let one = pool.get_with_capacity(1);
+    drop((empty, one));
+    assert_eq!(pool.size_histogram(), [(0, 1), (1, 1)]);
+}
+
+#[test]
+fn adaptive_prefill() {
+    let pool = Pool::builder(Vec::<u8>::new)
+        .adaptive(AdaptivePolicy::new(1.2, 16))
+        .build();
+    pool.prefill(4);
+    pool.recycle_batch(vec![Vec::new()]);
+    assert_eq!(pool.len(), 5);
+
+    // Once there's a peak the returned item is shed, but prefilling still
+    // stores.
+    drop(pool.get());
+    assert_eq!(pool.len(), 4);
+    pool.prefill(1);
+    assert_eq!(pool.len(), 5);
+
+    let pool = Pool::builder(Vec::<u8>::new)
+        .adaptive(AdaptivePolicy::new(1.2, 16))
+        .warm_on_first_get(4)
+        .build();
+    let item = pool.get();
+    assert_eq!(pool.len(), 4);
+    drop(item);
+}
+
+#[test]
+fn reserve_average_small() {
+    for len in [12, 100] {
+        let pool = Pool::builder(Vec::new)
+            .clear(|buf: &mut Vec<u8>| {
+                buf.clear();
+                buf.shrink_to_fit();
+            })
+            .reserve_average()
+            .build();
+        pool.recycle_batch(vec![vec![0; len]; 100]);
+        assert_eq!(pool.get().capacity(), len);
+    }
+}
+
+#[test]
+#[cfg(feature = "leak_detection")]
+fn get_array_location() {
+    let pool = Pool::new(Vec::<u8>::new);
+    let line = line!() + 1;
+    let guards = pool.get_array::<2>();
+    let location = format!("{}:{}:", file!(), line);
+    assert!(guards
+        .iter()
+        .all(|guard| format!("{:?}", guard).contains(&location)));
+}
@@ -0,0 +1,168 @@
+#![cfg(feature = "tokio")]
+
+use remem::{Pool, StatsSink};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+#[tokio::test]
+async fn async_clear() {
+    let cleared = Arc::new(AtomicUsize::new(0));
+    let c = cleared.clone();
+    let pool = Pool::with_async_clear(
+        || vec![0u8; 4],
+        move |mut v: Vec<u8>| {
+            let c = c.clone();
+            Box::pin(async move {
+                tokio::task::yield_now().await;
+                v.clear();
+                c.fetch_add(1, Ordering::SeqCst);
+                v
+            })
+        },
+    );
+
+    let mut item = pool.get();
+    item.push(1);
+    item.recycle().await;
+    assert_eq!(cleared.load(Ordering::SeqCst), 1);
+    assert_eq!(pool.len(), 1);
+
+    let item = pool.get();
+    assert!(item.is_empty());
+    drop(item);
+    while pool.is_empty() {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+    assert_eq!(cleared.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn cancel_task_holding_guard() {
+    let pool = Pool::with_clear(Vec::<u8>::new, Vec::clear);
+    let mut item = pool.get_async_guard();
+    item.push(1);
+    let task = tokio::spawn(async move {
+        let _item = item;
+        std::future::pending::<()>().await;
+    });
+    tokio::task::yield_now().await;
+    task.abort();
+    assert!(task.await.unwrap_err().is_cancelled());
+    assert_eq!(pool.len(), 1);
+    assert_eq!(pool.in_use(), 0);
+
+    let item = pool.get_async_guard();
+    assert!(item.is_empty());
+    item.recycle().await;
+    assert_eq!(pool.len(), 1);
+}
+
+#[tokio::test]
+async fn awaitable_guard() {
+    let pool = Pool::new(Vec::<u8>::new);
+    let (mut guard, mut completion) = pool.get_awaitable();
+    completion.push(1);
+    let (sender, receiver) = tokio::sync::oneshot::channel::<()>();
+
+    let consumer = tokio::spawn(async move {
+        receiver.await.unwrap();
+        assert_eq!(*completion, [1]);
+        completion.complete();
+    });
+    assert!(tokio::time::timeout(Duration::from_millis(10), &mut guard)
+        .await
+        .is_err());
+    assert_eq!(pool.len(), 0);
+
+    sender.send(()).unwrap();
+    guard.await;
+    assert_eq!(pool.len(), 1);
+    consumer.await.unwrap();
+}
+
+#[tokio::test]
+async fn recycle_records_returns() {
+    #[derive(Default)]
+    struct Returns(AtomicUsize);
+
+    impl StatsSink for Returns {
+        fn record_get(&self, _hit: bool) {}
+
+        fn record_return(&self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let returns = Arc::new(Returns::default());
+    let pool = Pool::builder(Vec::<u8>::new)
+        .async_clear(|mut v: Vec<u8>| {
+            Box::pin(async move {
+                v.clear();
+                v
+            })
+        })
+        .collect_stats_into(returns.clone())
+        .build();
+    pool.get().recycle().await;
+    pool.get_async_guard().recycle().await;
+    assert_eq!(returns.0.load(Ordering::SeqCst), 2);
+}
+
+/// A pool whose asynchronous clear waits for `release` to be notified.
+fn held_clear_pool(release: &Arc<Notify>) -> Pool<Vec<u8>> {
+    let release = release.clone();
+    Pool::with_async_clear(Vec::<u8>::new, move |v: Vec<u8>| {
+        let release = release.clone();
+        Box::pin(async move {
+            release.notified().await;
+            v
+        })
+    })
+}
+
+#[tokio::test]
+async fn drain_with_wait_gets_async_guards_recycled() {
+    let release = Arc::new(Notify::new());
+    let pool = held_clear_pool(&release);
+    let mut recycling = Box::pin(pool.get_async_guard().recycle());
+    assert!(
+        tokio::time::timeout(Duration::from_millis(10), &mut recycling)
+            .await
+            .is_err()
+    );
+    // The guard is still checked out while its item is being cleared.
+    assert_eq!(pool.in_use(), 1);
+
+    let drain = {
+        let pool = pool.clone();
+        std::thread::spawn(move || pool.drain_with_wait(Duration::from_secs(10)))
+    };
+    release.notify_one();
+    recycling.await;
+    assert_eq!(drain.join().unwrap().len(), 1);
+    assert_eq!(pool.in_use(), 0);
+}
+
+#[tokio::test]
+async fn drain_with_wait_gets_guards_recycled() {
+    let release = Arc::new(Notify::new());
+    let pool = held_clear_pool(&release);
+    let mut recycling = Box::pin(pool.get().recycle());
+    assert!(
+        tokio::time::timeout(Duration::from_millis(10), &mut recycling)
+            .await
+            .is_err()
+    );
+    assert_eq!(pool.in_use(), 1);
+
+    let drain = {
+        let pool = pool.clone();
+        std::thread::spawn(move || pool.drain_with_wait(Duration::from_secs(10)))
+    };
+    release.notify_one();
+    recycling.await;
+    assert_eq!(drain.join().unwrap().len(), 1);
+    assert_eq!(pool.in_use(), 0);
+}
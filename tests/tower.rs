@@ -0,0 +1,63 @@
+#![cfg(feature = "tower")]
+
+use remem::{GrowthPolicy, Pool, PoolLayer, PooledRequest};
+use std::future::{self, Future, Ready};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use tower::{Layer, Service};
+
+/// Returns the pooled item along with the request, so the test decides when
+/// it's returned.
+struct Echo;
+
+impl Service<PooledRequest<u32, Vec<u8>>> for Echo {
+    type Response = PooledRequest<u32, Vec<u8>>;
+    type Error = ();
+    type Future = Ready<Result<Self::Response, ()>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: PooledRequest<u32, Vec<u8>>) -> Self::Future {
+        future::ready(Ok(request))
+    }
+}
+
+#[derive(Default)]
+struct Flag(AtomicBool);
+
+impl Wake for Flag {
+    fn wake(self: Arc<Self>) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn readiness_follows_pool() {
+    let pool = Pool::builder(Vec::new)
+        .growth_policy(GrowthPolicy::FailOnEmpty)
+        .build();
+    pool.prefill(1);
+    let mut service = PoolLayer::new(pool.clone()).layer(Echo);
+    let woken = Arc::new(Flag::default());
+    let waker = Waker::from(woken.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    assert_eq!(service.poll_ready(&mut cx), Poll::Ready(Ok(())));
+    let mut call = Service::<u32>::call(&mut service, 7);
+    let response = match Pin::new(&mut call).poll(&mut cx) {
+        Poll::Ready(Ok(response)) => response,
+        _ => unreachable!(),
+    };
+    assert_eq!(response.request, 7);
+
+    assert_eq!(service.poll_ready(&mut cx), Poll::Pending);
+    assert!(!woken.0.load(Ordering::SeqCst));
+    drop(response);
+    assert!(woken.0.load(Ordering::SeqCst));
+    assert_eq!(service.poll_ready(&mut cx), Poll::Ready(Ok(())));
+    assert!(pool.is_empty());
+}